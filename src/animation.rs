@@ -0,0 +1,246 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    str::FromStr,
+    thread,
+    time::Duration,
+};
+
+use code_advent_2022::{Frame, Solution};
+
+use crate::cli::VisualizeOptions;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Ansi,
+    Text,
+    Ppm,
+    Pgm,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "ansi" => Ok(Format::Ansi),
+            "text" => Ok(Format::Text),
+            "ppm" => Ok(Format::Ppm),
+            "pgm" => Ok(Format::Pgm),
+            _ => Err(format!(
+                "Invalid format `{value}`, expected ansi, text, ppm or pgm"
+            )),
+        }
+    }
+}
+
+// draws the selected parts of the puzzles which can be visualized, the others are skipped
+pub fn run(puzzles: &[Box<dyn Solution>], options: &VisualizeOptions) -> Result<(), String> {
+    let mut visualized = 0;
+    for (idx, puzzle) in puzzles.iter().enumerate() {
+        let puzzle_info = puzzle.puzzle_info();
+        // the examples of the same day are told apart in the names of the images
+        let runs = puzzles
+            .iter()
+            .filter(|other| other.puzzle_info().day() == puzzle_info.day())
+            .count();
+        let run = puzzles[..idx]
+            .iter()
+            .filter(|other| other.puzzle_info().day() == puzzle_info.day())
+            .count();
+        let stem = match runs {
+            1 => format!("day{:02}", puzzle_info.day()),
+            _ => format!("day{:02}-{}", puzzle_info.day(), run + 1),
+        };
+
+        for part in options.selection.parts() {
+            let title = format!("{} - {part} part", puzzle_info.name());
+            let name = format!("{stem}_part{}", part.number());
+            let mut writer = Writer::new(options, &title, &name);
+            match puzzle.visualize(part, &mut |frame| writer.frame(frame)) {
+                None => {
+                    eprintln!("{}: Nothing to visualize", puzzle_info.name());
+                    break;
+                }
+                Some(Err(error)) => {
+                    return Err(format!("{}: Invalid input. {error}", puzzle_info.name()))
+                }
+                Some(Ok(())) => writer.finish()?,
+            }
+            visualized += 1;
+        }
+    }
+
+    match visualized {
+        0 => Err(String::from(
+            "None of the selected puzzles can be visualized",
+        )),
+        _ => Ok(()),
+    }
+}
+
+// keeps every n-th frame and the last one, the first error stops the writing
+struct Writer<'a> {
+    options: &'a VisualizeOptions,
+    title: &'a str,
+    name: &'a str,
+    count: usize,
+    written: usize,
+    // the last frame skipped, written at the end so the result is always shown
+    skipped: Option<Frame>,
+    error: Option<String>,
+}
+
+impl<'a> Writer<'a> {
+    fn new(options: &'a VisualizeOptions, title: &'a str, name: &'a str) -> Self {
+        Self {
+            options,
+            title,
+            name,
+            count: 0,
+            written: 0,
+            skipped: None,
+            error: None,
+        }
+    }
+
+    fn frame(&mut self, frame: &Frame) {
+        self.count += 1;
+        if self.error.is_some() {
+            return;
+        }
+        if (self.count - 1).is_multiple_of(self.options.every) {
+            self.skipped = None;
+            if let Err(error) = self.write(frame) {
+                self.error = Some(error);
+            }
+        } else {
+            match &mut self.skipped {
+                Some(skipped) => skipped.clone_from(frame),
+                None => self.skipped = Some(frame.clone()),
+            }
+        }
+    }
+
+    fn finish(mut self) -> Result<(), String> {
+        if let (Some(frame), None) = (self.skipped.take(), &self.error) {
+            self.write(&frame)?;
+        }
+        match self.error {
+            Some(error) => Err(error),
+            None => {
+                if let Some(extension) = self.extension() {
+                    let dir = Path::new(&self.options.out_dir);
+                    println!(
+                        "{}: {} frames written to {}/{}_*.{extension}",
+                        self.title,
+                        self.written,
+                        dir.display(),
+                        self.name
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn extension(&self) -> Option<&'static str> {
+        match self.options.format {
+            Format::Ansi | Format::Text => None,
+            Format::Ppm => Some("ppm"),
+            Format::Pgm => Some("pgm"),
+        }
+    }
+
+    fn write(&mut self, frame: &Frame) -> Result<(), String> {
+        let number = self.count - 1;
+        self.written += 1;
+        let content = match self.options.format {
+            // the cursor is moved home so the frames printed one after another animate in place
+            Format::Ansi => format!("\x1b[H\x1b[2K{} #{number}\n{}", self.title, frame.to_ansi()),
+            Format::Text => format!("{} #{number}\n{frame}\n\n", self.title),
+            Format::Ppm | Format::Pgm => {
+                let bytes = match self.options.format {
+                    Format::Ppm => frame.to_ppm(),
+                    _ => frame.to_pgm(),
+                };
+                let dir = Path::new(&self.options.out_dir);
+                fs::create_dir_all(dir)
+                    .map_err(|error| format!("Not able to create {}: {error}", dir.display()))?;
+                let path = dir.join(format!(
+                    "{}_{number:05}.{}",
+                    self.name,
+                    self.extension().unwrap()
+                ));
+                return fs::write(&path, bytes)
+                    .map_err(|error| format!("Not able to write {}: {error}", path.display()));
+            }
+        };
+
+        let mut stdout = io::stdout().lock();
+        // the first frame of the animation starts on a cleared screen
+        if self.options.format == Format::Ansi && self.written == 1 {
+            write!(stdout, "\x1b[2J").ok();
+        }
+        stdout
+            .write_all(content.as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|error| format!("Not able to print the frame: {error}"))?;
+        if self.options.format == Format::Ansi {
+            thread::sleep(Duration::from_millis(self.options.delay));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use code_advent_2022::registry;
+
+    use super::*;
+    use crate::cli::Selection;
+
+    #[test]
+    fn images_written() {
+        let dir = env::temp_dir().join(format!("code_advent_frames_{}", std::process::id()));
+        let options = VisualizeOptions {
+            selection: Selection::default(),
+            example: true,
+            format: Format::Pgm,
+            out_dir: dir.to_string_lossy().into_owned(),
+            every: 10,
+            delay: 0,
+        };
+        let puzzles: Vec<Box<dyn Solution>> = registry()
+            .iter()
+            .filter(|registration| registration.day == 9)
+            .flat_map(|registration| registration.examples())
+            .map(|(puzzle, _)| puzzle)
+            .collect();
+        run(&puzzles, &options).unwrap();
+
+        let mut files: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|file| file.starts_with("day09-1_part1"))
+            .collect();
+        files.sort();
+        // the 24 steps of the first example are written every 10th and the last one
+        assert_eq!(
+            vec![
+                "day09-1_part1_00000.pgm",
+                "day09-1_part1_00010.pgm",
+                "day09-1_part1_00020.pgm",
+                "day09-1_part1_00023.pgm"
+            ],
+            files
+        );
+        let image = fs::read(dir.join("day09-1_part1_00023.pgm")).unwrap();
+        assert!(image.starts_with(b"P5\n6 5\n255\n"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    // multi-line output, e.g. the image drawn by the CRT from the tenth puzzle
+    Rendering(Vec<String>),
+}
+
+impl Answer {
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Rendering(_))
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Integer(value as i64)
+                }
+            }
+        )*
+    };
+}
+
+integer_answer!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(String::from(value))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{n}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::Rendering(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!("13140", Answer::from(13140).to_string());
+        assert_eq!("CMZ", Answer::from("CMZ").to_string());
+        assert_eq!(
+            "#.\n.#",
+            Answer::Rendering(vec![String::from("#."), String::from(".#")]).to_string()
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use code_advent_2022::{configure, registry, Part, Solution};
+
+use crate::{
+    cli::BatchOptions,
+    isolation,
+    verify::{self, Answers},
+};
+
+// the accepted answers of a batch are kept with its inputs, keyed by the file names
+const ANSWERS_FILE: &str = "answers.txt";
+
+enum Cell {
+    Answer(String),
+    Failed,
+    Skipped,
+}
+
+enum Check {
+    Pass,
+    Fail,
+    Unknown,
+    Error,
+}
+
+struct Row {
+    file: String,
+    cells: Vec<Cell>,
+    check: Check,
+}
+
+// solves the day for every input of the directory, a broken input only fails its own row
+pub fn run(options: &BatchOptions) -> Result<(), String> {
+    let registry = registry();
+    let registration = registry
+        .iter()
+        .find(|registration| registration.day == options.day)
+        .ok_or_else(|| format!("There is no puzzle registered for day {}", options.day))?;
+
+    let dir = Path::new(&options.dir);
+    let answers_path = match &options.answers_path {
+        Some(path) => PathBuf::from(path),
+        None => dir.join(ANSWERS_FILE),
+    };
+    let answers: Answers<String> = verify::load_answers(&answers_path)?;
+    let overrides = options.params.overrides()?;
+    let files = input_files(dir, &answers_path)?;
+    if files.is_empty() {
+        return Err(format!("There are no inputs in {}", dir.display()));
+    }
+
+    let parts = match options.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+    let mut problems = Vec::new();
+    let rows: Vec<Row> = files
+        .iter()
+        .map(|path| {
+            let file = path.file_name().unwrap().to_string_lossy().into_owned();
+            let puzzle = registration.load_file(path).and_then(|mut puzzle| {
+                configure(puzzle.as_mut(), &overrides)?;
+                Ok(puzzle)
+            });
+            let puzzle = match puzzle {
+                Ok(puzzle) => puzzle,
+                Err(error) => {
+                    problems.push(format!("{file}: {error}"));
+                    let cells = Part::ALL.iter().map(|_| Cell::Failed).collect();
+                    return Row {
+                        file,
+                        cells,
+                        check: Check::Error,
+                    };
+                }
+            };
+            solve(puzzle.as_ref(), file, &parts, &answers, &mut problems)
+        })
+        .collect();
+
+    print_table(&rows);
+    for problem in &problems {
+        eprintln!("{problem}");
+    }
+
+    let count = |check: fn(&Check) -> bool| rows.iter().filter(|row| check(&row.check)).count();
+    let (failed, errors) = (
+        count(|check| matches!(check, Check::Fail)),
+        count(|check| matches!(check, Check::Error)),
+    );
+    println!(
+        "{} passed, {failed} failed, {errors} error(s), {} without accepted answers",
+        count(|check| matches!(check, Check::Pass)),
+        count(|check| matches!(check, Check::Unknown))
+    );
+    match failed + errors {
+        0 => Ok(()),
+        _ => Err(format!(
+            "{} input(s) of the batch did not pass",
+            failed + errors
+        )),
+    }
+}
+
+// every text file of the directory except the accepted answers, ordered by the name
+fn input_files(dir: &Path, answers_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|error| format!("Not able to read the directory {}: {error}", dir.display()))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+        .filter(|path| path != answers_path)
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn solve(
+    puzzle: &dyn Solution,
+    file: String,
+    parts: &[Part],
+    answers: &Answers<String>,
+    problems: &mut Vec<String>,
+) -> Row {
+    let (mut passed, mut failed, mut errors) = (0, 0, 0);
+    let cells = Part::ALL
+        .into_iter()
+        .map(|part| {
+            if !parts.contains(&part) {
+                return Cell::Skipped;
+            }
+            let answer = match isolation::solve(puzzle, part) {
+                Ok(answer) => answer.to_string(),
+                Err(error) => {
+                    problems.push(format!("{file}: Failed to solve the {part} part. {error}"));
+                    errors += 1;
+                    return Cell::Failed;
+                }
+            };
+            match answers.get(&(file.clone(), part.number())) {
+                Some(expected) if *expected == answer => passed += 1,
+                Some(expected) => {
+                    problems.push(format!(
+                        "{file}: The {part} part failed, expected:\n{expected}\nfound:\n{answer}"
+                    ));
+                    failed += 1;
+                }
+                None => {}
+            }
+            Cell::Answer(answer)
+        })
+        .collect();
+
+    let check = match (passed, failed, errors) {
+        (_, _, 1..) => Check::Error,
+        (_, 1.., _) => Check::Fail,
+        (1.., _, _) => Check::Pass,
+        _ => Check::Unknown,
+    };
+    Row { file, cells, check }
+}
+
+fn print_table(rows: &[Row]) {
+    // multi-line answers do not fit into the table, their rows are counted instead
+    let text = |cell: &Cell| match cell {
+        Cell::Answer(answer) if answer.contains('\n') => {
+            format!("<{} rows>", answer.lines().count())
+        }
+        Cell::Answer(answer) => answer.clone(),
+        Cell::Failed => String::from("error"),
+        Cell::Skipped => String::from("-"),
+    };
+    let file_width = rows.iter().map(|row| row.file.len()).max().unwrap_or(0);
+    let answer_width = rows
+        .iter()
+        .flat_map(|row| row.cells.iter().map(|cell| text(cell).len()))
+        .chain(Some("Part 1".len()))
+        .max()
+        .unwrap_or(0);
+
+    println!(
+        "{:<file_width$}  {:<answer_width$}  {:<answer_width$}  Check",
+        "File", "Part 1", "Part 2"
+    );
+    for row in rows {
+        let check = match row.check {
+            Check::Pass => "pass",
+            Check::Fail => "fail",
+            Check::Unknown => "-",
+            Check::Error => "error",
+        };
+        println!(
+            "{:<file_width$}  {:<answer_width$}  {:<answer_width$}  {check}",
+            row.file,
+            text(&row.cells[0]),
+            text(&row.cells[1])
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn batch_of_inputs() {
+        let dir = env::temp_dir().join("code_advent_2022_batch");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "1000\n2000\n\n4000").unwrap();
+        fs::write(dir.join("b.txt"), "1000\nx").unwrap();
+        fs::write(dir.join("c.txt"), "5").unwrap();
+        fs::write(dir.join("notes.md"), "not an input").unwrap();
+        fs::write(dir.join(ANSWERS_FILE), "a.txt 1 4000\nc.txt 1 6\n").unwrap();
+
+        let answers_path = dir.join(ANSWERS_FILE);
+        let files = input_files(&dir, &answers_path).unwrap();
+        let names: Vec<_> = files.iter().map(|path| path.file_name().unwrap()).collect();
+        assert_eq!(vec!["a.txt", "b.txt", "c.txt"], names);
+
+        let registration = &registry()[0];
+        let answers: Answers<String> = verify::load_answers(&answers_path).unwrap();
+        let checks: Vec<Check> = files
+            .iter()
+            .map(|path| {
+                let puzzle = registration.load_file(path).unwrap();
+                let file = path.file_name().unwrap().to_string_lossy().into_owned();
+                solve(puzzle.as_ref(), file, &Part::ALL, &answers, &mut Vec::new()).check
+            })
+            .collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(
+            checks[..],
+            [Check::Pass, Check::Error, Check::Fail]
+        ));
+    }
+}
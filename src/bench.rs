@@ -0,0 +1,205 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    time::{Duration, Instant},
+};
+
+use code_advent_2022::{Part, Solution};
+
+use crate::{cli::BenchOptions, isolation};
+
+// a stage slower than the saved median by more than this ratio is reported as a regression
+const REGRESSION_THRESHOLD: f64 = 0.1;
+
+// the saved medians keyed by the day and the stage
+type Results = BTreeMap<(u8, String), Duration>;
+
+struct Timings {
+    min: Duration,
+    median: Duration,
+    max: Duration,
+}
+
+impl Timings {
+    fn measure<F: Fn() -> Result<(), String>>(runs: usize, f: F) -> Result<Self, String> {
+        let mut samples = Vec::with_capacity(runs);
+        for _ in 0..runs {
+            let start = Instant::now();
+            f()?;
+            samples.push(start.elapsed());
+        }
+        samples.sort();
+
+        Ok(Self {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        })
+    }
+}
+
+fn stage_name(part: Part) -> &'static str {
+    match part {
+        Part::First => "part1",
+        Part::Second => "part2",
+    }
+}
+
+pub fn run(puzzles: &[Box<dyn Solution>], options: &BenchOptions) -> Result<(), String> {
+    let mut results = load_results(&options.results_path)?;
+    let name_width = puzzles
+        .iter()
+        .map(|puzzle| puzzle.puzzle_info().name().len())
+        .max()
+        .unwrap_or(0);
+
+    println!(
+        "{:>3}  {:<name_width$}  {:<6} {:>10} {:>10} {:>10} {:>10}  Change",
+        "Day", "Puzzle", "Stage", "Min", "Median", "Max", "Saved"
+    );
+
+    let mut failures = 0;
+    let mut regressions = 0;
+    for puzzle in puzzles {
+        let day = puzzle.puzzle_info().day();
+
+        // the input is parsed once for the parts, the parse stage parses it again on every run
+        let parsed = isolation::parse(puzzle.as_ref()).map(|_| ());
+        let mut stages = vec![(
+            "parse",
+            parsed.and_then(|_| {
+                Timings::measure(options.runs, || isolation::reparse(puzzle.as_ref()))
+            }),
+        )];
+        if stages[0].1.is_ok() {
+            for part in options.selection.parts() {
+                let timings = Timings::measure(options.runs, || {
+                    isolation::solve(puzzle.as_ref(), part).map(|_| ())
+                });
+                stages.push((stage_name(part), timings));
+            }
+        }
+
+        let name = puzzle.puzzle_info().name();
+        for (stage, timings) in stages {
+            let timings = match timings {
+                Ok(timings) => timings,
+                Err(error) => {
+                    eprintln!("{name}: Failed to benchmark the {stage} stage. {error}");
+                    failures += 1;
+                    continue;
+                }
+            };
+
+            let key = (day, String::from(stage));
+            let change = match results.get(&key) {
+                Some(saved) => {
+                    let saved_secs = saved.as_secs_f64().max(f64::EPSILON);
+                    let ratio = timings.median.as_secs_f64() / saved_secs - 1.0;
+                    let verdict = if ratio > REGRESSION_THRESHOLD {
+                        regressions += 1;
+                        "  regression"
+                    } else {
+                        ""
+                    };
+                    (
+                        format!("{saved:.1?}"),
+                        format!("{:+.1}%{verdict}", ratio * 100.0),
+                    )
+                }
+                None => (String::from("-"), String::from("-")),
+            };
+
+            println!(
+                "{:>3}  {:<name_width$}  {:<6} {:>10} {:>10} {:>10} {:>10}  {}",
+                day,
+                name,
+                stage,
+                format!("{:.1?}", timings.min),
+                format!("{:.1?}", timings.median),
+                format!("{:.1?}", timings.max),
+                change.0,
+                change.1
+            );
+            results.insert(key, timings.median);
+        }
+    }
+
+    if regressions > 0 {
+        println!("{regressions} stage(s) got slower than the saved results");
+    }
+    if options.save {
+        save_results(&options.results_path, &results)?;
+    }
+
+    match failures {
+        0 => Ok(()),
+        _ => Err(format!("{failures} stage(s) could not be benchmarked")),
+    }
+}
+
+// every line holds the day, the stage and the median in nanoseconds separated by tabs
+fn load_results(path: &str) -> Result<Results, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Ok(Results::new()),
+    };
+
+    let mut results = Results::new();
+    for (idx, line) in content.lines().enumerate() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let (day, stage, nanos) = match fields[..] {
+            [day, stage, nanos] => (day.parse(), stage, nanos.parse()),
+            _ => {
+                return Err(format!(
+                    "Invalid benchmark result in {path} on line {}",
+                    idx + 1
+                ))
+            }
+        };
+        match (day, nanos) {
+            (Ok(day), Ok(nanos)) => {
+                results.insert((day, String::from(stage)), Duration::from_nanos(nanos));
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid benchmark result in {path} on line {}",
+                    idx + 1
+                ))
+            }
+        }
+    }
+    Ok(results)
+}
+
+fn save_results(path: &str, results: &Results) -> Result<(), String> {
+    let content: String = results
+        .iter()
+        .map(|((day, stage), median)| format!("{day}\t{stage}\t{}\n", median.as_nanos()))
+        .collect();
+    fs::write(path, content).map_err(|error| format!("Not able to save the results: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_of_runs() {
+        let timings = Timings::measure(5, || Ok(())).unwrap();
+        assert!(timings.min <= timings.median && timings.median <= timings.max);
+    }
+
+    #[test]
+    fn saved_results_round_trip() {
+        let path = std::env::temp_dir().join("code_advent_2022_bench.tsv");
+        let path = path.to_str().unwrap();
+        let mut results = Results::new();
+        results.insert((1, String::from("parse")), Duration::from_nanos(1500));
+        results.insert((15, String::from("part2")), Duration::from_millis(3));
+
+        save_results(path, &results).unwrap();
+        assert_eq!(results, load_results(path).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+}
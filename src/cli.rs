@@ -0,0 +1,702 @@
+use std::path::Path;
+
+use code_advent_2022::{read_config, InputSource, Override, Part, Registration};
+
+use crate::{animation, report::Format};
+
+pub const USAGE: &str = "Usage:
+    code_advent_2022 [run] [SELECTION] [--example] [--format human|json|csv] [--jobs N]
+    code_advent_2022 bench [SELECTION] [--runs N] [--results FILE] [--save]
+    code_advent_2022 verify [SELECTION] [--answers FILE] [--record]
+    code_advent_2022 check [SELECTION]
+    code_advent_2022 batch N DIR [--part 1|2] [--answers FILE] [PARAMETERS]
+    code_advent_2022 new-day N TITLE [--inputs DIR]
+    code_advent_2022 generate N [--seed N] [--size N]
+    code_advent_2022 visualize [SELECTION] [--example] [--format ansi|text|ppm|pgm] [--out DIR]
+                     [--every N] [--delay MS]
+    code_advent_2022 list
+    code_advent_2022 help
+
+Selection:
+    --day N, -d N        solve the puzzle of the day N, may be repeated
+    --days FROM-TO       solve a range or a comma separated list of days
+    --part 1|2, -p 1|2   solve only one part of the puzzles
+    --input FILE, -i FILE
+                         read the input of the single selected day from FILE, `-` for stdin
+    --inputs DIR         read the inputs from DIR instead of `inputs`, the CODE_ADVENT_INPUTS
+                         environment variable sets the directory as well
+
+Parameters, accepted along with any SELECTION:
+    --param DAY.NAME=VALUE
+                         override a parameter of the puzzle, may be repeated
+    --config FILE        read the parameters from FILE instead of `code_advent.conf`
+
+Without any selection all the registered puzzles are solved.
+The config holds a single DAY.NAME=VALUE per line, `--param` takes precedence over it.
+`list` shows the parameters of the puzzles with their defaults.
+`--example` solves the official examples instead of the inputs and checks their answers, unless
+their day has a parameter overridden.
+The JSON and CSV formats report the name, day, part, status (ok, error or skipped),
+answer, error and elapsed milliseconds of every part of the selected days.
+`--jobs` solves up to N puzzles at once, the results are still reported in day order.
+The benchmark compares the timings with the results stored in the FILE
+(`bench.tsv` by default), `--save` replaces them with the current run.
+The verification compares the answers with the accepted ones stored in the FILE
+(`answers.txt` within the inputs directory by default), `--record` adds the answers
+missing there.
+`check` only parses the inputs, it reports the problems found in them and a summary
+of what was parsed.
+`batch` solves the day N for every `.txt` input in the DIR and checks the answers accepted
+in the FILE (`answers.txt` within the DIR by default), its lines hold the file name,
+the part and the answer.
+`new-day` creates the module of the puzzle of the day N named by the TITLE from the puzzle
+description, registers it with the runner and creates its empty input.
+`generate` prints a random input of the day N generated from the seed, the size tells roughly
+how many items it holds. The parameters the input is meant to be solved with are printed
+to the stderr.
+`visualize` draws the simulations of the days 9, 10, 12, 14 and 15 frame by frame, either as
+an animation in the terminal (`ansi`, `--delay` milliseconds between the frames), as plain
+text, or as numbered PPM/PGM images written to the DIR (`frames` by default). `--every` keeps
+only every N-th frame, the last one is always kept.";
+
+pub enum Command {
+    Run(RunOptions),
+    Bench(BenchOptions),
+    Verify(VerifyOptions),
+    Check(Selection),
+    Batch(BatchOptions),
+    NewDay(NewDayOptions),
+    Generate(GenerateOptions),
+    Visualize(VisualizeOptions),
+    List,
+    Help,
+}
+
+impl Command {
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        match args.next().as_deref() {
+            None => Ok(Command::Run(RunOptions::default())),
+            Some("run") => Ok(Command::Run(RunOptions::from_args(args)?)),
+            Some("bench") => Ok(Command::Bench(BenchOptions::from_args(args)?)),
+            Some("verify") => Ok(Command::Verify(VerifyOptions::from_args(args)?)),
+            Some("check") => Ok(Command::Check(Selection::from_args(args)?)),
+            Some("batch") => Ok(Command::Batch(BatchOptions::from_args(args)?)),
+            Some("new-day") => Ok(Command::NewDay(NewDayOptions::from_args(args)?)),
+            Some("generate") => Ok(Command::Generate(GenerateOptions::from_args(args)?)),
+            Some("visualize") => Ok(Command::Visualize(VisualizeOptions::from_args(args)?)),
+            Some("help" | "--help" | "-h") => Ok(Command::Help),
+            Some(flag) if flag.starts_with('-') => {
+                let args = std::iter::once(String::from(flag)).chain(args);
+                Ok(Command::Run(RunOptions::from_args(args)?))
+            }
+            Some("list") => match args.next() {
+                None => Ok(Command::List),
+                Some(arg) => Err(format!("Unexpected argument `{arg}` for `list`")),
+            },
+            Some(command) => Err(format!("Unknown command `{command}`")),
+        }
+    }
+}
+
+// the parameters are read from this file in the working directory unless `--config` is given
+const CONFIG_FILE: &str = "code_advent.conf";
+
+#[derive(Default)]
+pub struct ParamFlags {
+    params: Vec<Override>,
+    config: Option<String>,
+}
+
+impl ParamFlags {
+    fn parse_flag<I: Iterator<Item = String>>(
+        &mut self,
+        flag: &str,
+        flags: &mut Flags<I>,
+    ) -> Result<bool, String> {
+        match flag {
+            "--param" => self.params.push(flags.value(flag)?.parse()?),
+            "--config" => self.config = Some(flags.value(flag)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    // the parameters of the config followed by the ones of the command line
+    pub fn overrides(&self) -> Result<Vec<Override>, String> {
+        let mut overrides = match &self.config {
+            Some(path) if !Path::new(path).exists() => {
+                return Err(format!("The config {path} does not exist"))
+            }
+            Some(path) => read_config(Path::new(path))?,
+            None => read_config(Path::new(CONFIG_FILE))?,
+        };
+        overrides.extend(self.params.iter().cloned());
+        Ok(overrides)
+    }
+}
+
+#[derive(Default)]
+pub struct Selection {
+    days: Vec<u8>,
+    part: Option<Part>,
+    input: Option<String>,
+    inputs_dir: Option<String>,
+    params: ParamFlags,
+}
+
+impl Selection {
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = Flags { args };
+        let mut selection = Selection::default();
+
+        while let Some(flag) = flags.next() {
+            if !selection.parse_flag(&flag, &mut flags)? {
+                return Err(format!("Unknown argument `{flag}`"));
+            }
+        }
+
+        Ok(selection)
+    }
+
+    // returns false when the flag does not belong to the selection
+    fn parse_flag<I: Iterator<Item = String>>(
+        &mut self,
+        flag: &str,
+        flags: &mut Flags<I>,
+    ) -> Result<bool, String> {
+        match flag {
+            "--day" | "-d" => self.days.push(parse_day(&flags.value(flag)?)?),
+            "--days" => self.days.extend(parse_days(&flags.value(flag)?)?),
+            "--part" | "-p" => self.part = Some(parse_part(&flags.value(flag)?)?),
+            "--input" | "-i" => self.input = Some(flags.value(flag)?),
+            "--inputs" => self.inputs_dir = Some(flags.value(flag)?),
+            _ => return self.params.parse_flag(flag, flags),
+        }
+
+        self.days.sort();
+        self.days.dedup();
+        Ok(true)
+    }
+
+    pub fn check_days(&self, registry: &[Registration]) -> Result<(), String> {
+        let is_registered = |day: &u8| registry.iter().any(|puzzle| puzzle.day == *day);
+        match self.days.iter().find(|day| !is_registered(day)) {
+            Some(day) => Err(format!("There is no puzzle registered for day {day}")),
+            None => Ok(()),
+        }
+    }
+
+    pub fn input_source(&self) -> Result<InputSource, String> {
+        if self.input.is_some() && self.days.len() != 1 {
+            return Err(String::from(
+                "An explicit input requires exactly one selected day",
+            ));
+        }
+        Ok(InputSource::new(
+            self.inputs_dir.as_deref(),
+            self.input.as_deref(),
+        ))
+    }
+
+    pub fn overrides(&self) -> Result<Vec<Override>, String> {
+        self.params.overrides()
+    }
+
+    pub fn includes_day(&self, day: u8) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    pub fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+}
+
+pub struct RunOptions {
+    pub selection: Selection,
+    pub format: Format,
+    pub jobs: usize,
+    pub example: bool,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            selection: Selection::default(),
+            format: Format::default(),
+            jobs: 1,
+            example: false,
+        }
+    }
+}
+
+impl RunOptions {
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = Flags { args };
+        let mut options = RunOptions::default();
+
+        while let Some(flag) = flags.next() {
+            match flag.as_str() {
+                "--example" | "-e" => options.example = true,
+                "--format" | "-f" => options.format = flags.value(&flag)?.parse()?,
+                "--jobs" | "-j" => {
+                    options.jobs = match flags.value(&flag)?.parse() {
+                        Ok(jobs) if jobs > 0 => jobs,
+                        _ => return Err(String::from("The number of jobs must be positive")),
+                    }
+                }
+                _ => {
+                    if !options.selection.parse_flag(&flag, &mut flags)? {
+                        return Err(format!("Unknown argument `{flag}`"));
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub struct BenchOptions {
+    pub selection: Selection,
+    pub runs: usize,
+    pub results_path: String,
+    pub save: bool,
+}
+
+impl BenchOptions {
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = Flags { args };
+        let mut options = BenchOptions {
+            selection: Selection::default(),
+            runs: 10,
+            results_path: String::from("bench.tsv"),
+            save: false,
+        };
+
+        while let Some(flag) = flags.next() {
+            match flag.as_str() {
+                "--runs" | "-n" => {
+                    options.runs = match flags.value(&flag)?.parse() {
+                        Ok(runs) if runs > 0 => runs,
+                        _ => return Err(String::from("The number of runs must be positive")),
+                    }
+                }
+                "--results" => options.results_path = flags.value(&flag)?,
+                "--save" => options.save = true,
+                _ => {
+                    if !options.selection.parse_flag(&flag, &mut flags)? {
+                        return Err(format!("Unknown argument `{flag}`"));
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub struct VerifyOptions {
+    pub selection: Selection,
+    pub answers_path: Option<String>,
+    pub record: bool,
+}
+
+impl VerifyOptions {
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = Flags { args };
+        let mut options = VerifyOptions {
+            selection: Selection::default(),
+            answers_path: None,
+            record: false,
+        };
+
+        while let Some(flag) = flags.next() {
+            match flag.as_str() {
+                "--answers" => options.answers_path = Some(flags.value(&flag)?),
+                "--record" => options.record = true,
+                _ => {
+                    if !options.selection.parse_flag(&flag, &mut flags)? {
+                        return Err(format!("Unknown argument `{flag}`"));
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+pub struct BatchOptions {
+    pub day: u8,
+    pub dir: String,
+    pub part: Option<Part>,
+    pub answers_path: Option<String>,
+    pub params: ParamFlags,
+}
+
+impl BatchOptions {
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = Flags { args };
+        let mut day = None;
+        let mut dir = None;
+        let mut part = None;
+        let mut answers_path = None;
+        let mut params = ParamFlags::default();
+
+        while let Some(arg) = flags.next() {
+            match arg.as_str() {
+                "--part" | "-p" => part = Some(parse_part(&flags.value(&arg)?)?),
+                "--answers" => answers_path = Some(flags.value(&arg)?),
+                _ if params.parse_flag(&arg, &mut flags)? => {}
+                _ if day.is_none() => day = Some(parse_day(&arg)?),
+                _ if dir.is_none() => dir = Some(arg),
+                _ => return Err(format!("Unexpected argument `{arg}` for `batch`")),
+            }
+        }
+
+        match (day, dir) {
+            (Some(day), Some(dir)) => Ok(BatchOptions {
+                day,
+                dir,
+                part,
+                answers_path,
+                params,
+            }),
+            _ => Err(String::from(
+                "The `batch` command expects the day and the directory",
+            )),
+        }
+    }
+}
+
+pub struct NewDayOptions {
+    pub day: u8,
+    pub title: String,
+    pub inputs_dir: Option<String>,
+}
+
+impl NewDayOptions {
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = Flags { args };
+        let mut day = None;
+        let mut title = None;
+        let mut inputs_dir = None;
+
+        while let Some(arg) = flags.next() {
+            match arg.as_str() {
+                "--inputs" => inputs_dir = Some(flags.value(&arg)?),
+                _ if day.is_none() => day = Some(parse_day(&arg)?),
+                _ if title.is_none() => title = Some(arg),
+                _ => return Err(format!("Unexpected argument `{arg}` for `new-day`")),
+            }
+        }
+
+        match (day, title) {
+            (Some(day), Some(title)) if !title.trim().is_empty() => Ok(NewDayOptions {
+                day,
+                title: String::from(title.trim()),
+                inputs_dir,
+            }),
+            _ => Err(String::from(
+                "The `new-day` command expects the day and the title",
+            )),
+        }
+    }
+}
+
+pub struct GenerateOptions {
+    pub day: u8,
+    pub seed: u64,
+    pub size: usize,
+}
+
+impl GenerateOptions {
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = Flags { args };
+        let mut day = None;
+        let mut seed = 0;
+        let mut size = 100;
+
+        while let Some(arg) = flags.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = flags.value(&arg)?;
+                    seed = value
+                        .parse()
+                        .map_err(|_| format!("Invalid seed `{value}`"))?;
+                }
+                "--size" => {
+                    let value = flags.value(&arg)?;
+                    size = value
+                        .parse()
+                        .map_err(|_| format!("Invalid size `{value}`"))?;
+                }
+                _ if day.is_none() => day = Some(parse_day(&arg)?),
+                _ => return Err(format!("Unexpected argument `{arg}` for `generate`")),
+            }
+        }
+
+        match day {
+            Some(day) => Ok(GenerateOptions { day, seed, size }),
+            None => Err(String::from("The `generate` command expects the day")),
+        }
+    }
+}
+
+pub struct VisualizeOptions {
+    pub selection: Selection,
+    pub example: bool,
+    pub format: animation::Format,
+    pub out_dir: String,
+    pub every: usize,
+    pub delay: u64,
+}
+
+impl VisualizeOptions {
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut flags = Flags { args };
+        let mut options = VisualizeOptions {
+            selection: Selection::default(),
+            example: false,
+            format: animation::Format::default(),
+            out_dir: String::from("frames"),
+            every: 1,
+            delay: 40,
+        };
+
+        while let Some(flag) = flags.next() {
+            match flag.as_str() {
+                "--example" | "-e" => options.example = true,
+                "--format" | "-f" => options.format = flags.value(&flag)?.parse()?,
+                "--out" => options.out_dir = flags.value(&flag)?,
+                "--every" => {
+                    options.every = match flags.value(&flag)?.parse() {
+                        Ok(every) if every > 0 => every,
+                        _ => return Err(String::from("The frame interval must be positive")),
+                    }
+                }
+                "--delay" => {
+                    let value = flags.value(&flag)?;
+                    options.delay = value
+                        .parse()
+                        .map_err(|_| format!("Invalid delay `{value}`"))?;
+                }
+                _ => {
+                    if !options.selection.parse_flag(&flag, &mut flags)? {
+                        return Err(format!("Unknown argument `{flag}`"));
+                    }
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+struct Flags<I: Iterator<Item = String>> {
+    args: I,
+}
+
+impl<I: Iterator<Item = String>> Flags<I> {
+    fn next(&mut self) -> Option<String> {
+        self.args.next()
+    }
+
+    fn value(&mut self, flag: &str) -> Result<String, String> {
+        self.args
+            .next()
+            .ok_or_else(|| format!("Missing value for `{flag}`"))
+    }
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    match value.trim().parse() {
+        Ok(day) if day > 0 => Ok(day),
+        _ => Err(format!("Invalid day `{value}`")),
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part, String> {
+    match value {
+        "1" => Ok(Part::First),
+        "2" => Ok(Part::Second),
+        _ => Err(format!("Invalid part `{value}`, expected 1 or 2")),
+    }
+}
+
+// accepts a single day, an inclusive range like `3-7` or a comma separated list of both
+fn parse_days(value: &str) -> Result<Vec<u8>, String> {
+    let mut days = Vec::new();
+    for chunk in value.split(',') {
+        match chunk.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse_day(from)?, parse_day(to)?);
+                if from > to {
+                    return Err(format!("Invalid range of days `{chunk}`"));
+                }
+                days.extend(from..=to);
+            }
+            None => days.push(parse_day(chunk)?),
+        }
+    }
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        Command::from_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn run_selected_days_and_part() {
+        match parse("run --days 3-5,9 --day 12 --part 2 --format json --jobs 4 --example --param 9.first_knots=3") {
+            Ok(Command::Run(options)) => {
+                assert_eq!(vec![3, 4, 5, 9, 12], options.selection.days);
+                assert_eq!(vec![Part::Second], options.selection.parts());
+                assert_eq!(Format::Json, options.format);
+                assert_eq!(4, options.jobs);
+                assert!(options.example);
+                assert_eq!(
+                    Ok(vec!["9.first_knots=3".parse().unwrap()]),
+                    options.selection.params.overrides()
+                );
+            }
+            _ => panic!("Expected run command"),
+        }
+    }
+
+    #[test]
+    fn run_everything_by_default() {
+        match parse("") {
+            Ok(Command::Run(options)) => {
+                assert!(options.selection.includes_day(15));
+                assert_eq!(vec![Part::First, Part::Second], options.selection.parts());
+                assert_eq!(Format::Human, options.format);
+            }
+            _ => panic!("Expected run command"),
+        }
+    }
+
+    #[test]
+    fn bench_options() {
+        match parse("bench --runs 5 --day 8 --save") {
+            Ok(Command::Bench(options)) => {
+                assert_eq!(5, options.runs);
+                assert!(options.save);
+                assert_eq!("bench.tsv", options.results_path);
+                assert_eq!(vec![8], options.selection.days);
+            }
+            _ => panic!("Expected bench command"),
+        }
+    }
+
+    #[test]
+    fn verify_options() {
+        match parse("verify --answers mine.txt --part 1") {
+            Ok(Command::Verify(options)) => {
+                assert_eq!(Some(String::from("mine.txt")), options.answers_path);
+                assert!(!options.record);
+                assert_eq!(vec![Part::First], options.selection.parts());
+            }
+            _ => panic!("Expected verify command"),
+        }
+    }
+
+    #[test]
+    fn check_selection() {
+        match parse("check --days 8,12 --inputs mine") {
+            Ok(Command::Check(selection)) => {
+                assert_eq!(vec![8, 12], selection.days);
+                assert_eq!(Some(String::from("mine")), selection.inputs_dir);
+            }
+            _ => panic!("Expected check command"),
+        }
+        assert!(parse("check --record").is_err());
+    }
+
+    #[test]
+    fn batch_options() {
+        match parse("batch 15 inputs/15 --part 1 --param 15.row=10") {
+            Ok(Command::Batch(options)) => {
+                assert_eq!(15, options.day);
+                assert_eq!("inputs/15", options.dir);
+                assert_eq!("row", options.params.params[0].name);
+                assert_eq!(Some(Part::First), options.part);
+                assert_eq!(None, options.answers_path);
+            }
+            _ => panic!("Expected batch command"),
+        }
+        assert!(parse("batch 12").is_err());
+    }
+
+    #[test]
+    fn new_day_options() {
+        let args = ["new-day", "16", "Proboscidea Volcanium", "--inputs", "mine"];
+        match Command::from_args(args.into_iter().map(String::from)) {
+            Ok(Command::NewDay(options)) => {
+                assert_eq!(16, options.day);
+                assert_eq!("Proboscidea Volcanium", options.title);
+                assert_eq!(Some(String::from("mine")), options.inputs_dir);
+            }
+            _ => panic!("Expected new-day command"),
+        }
+    }
+
+    #[test]
+    fn generate_options() {
+        match parse("generate 12 --size 40 --seed 7") {
+            Ok(Command::Generate(options)) => {
+                assert_eq!((12, 7, 40), (options.day, options.seed, options.size));
+            }
+            _ => panic!("Expected generate command"),
+        }
+        match parse("generate 3") {
+            Ok(Command::Generate(options)) => assert_eq!((0, 100), (options.seed, options.size)),
+            _ => panic!("Expected generate command"),
+        }
+        assert!(parse("generate").is_err());
+        assert!(parse("generate 3 --seed x").is_err());
+    }
+
+    #[test]
+    fn visualize_options() {
+        match parse("visualize --day 14 --part 2 --format ppm --out sand --every 50 --example") {
+            Ok(Command::Visualize(options)) => {
+                assert_eq!(vec![14], options.selection.days);
+                assert_eq!(vec![Part::Second], options.selection.parts());
+                assert_eq!(animation::Format::Ppm, options.format);
+                assert_eq!("sand", options.out_dir);
+                assert_eq!((50, 40), (options.every, options.delay));
+                assert!(options.example);
+            }
+            _ => panic!("Expected visualize command"),
+        }
+        assert!(parse("visualize --format gif").is_err());
+        assert!(parse("visualize --every 0").is_err());
+        assert!(parse("visualize --delay x").is_err());
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert!(parse("run --days 7-3").is_err());
+        assert!(parse("run --part 3").is_err());
+        assert!(parse("run --format xml").is_err());
+        assert!(parse("run --jobs 0").is_err());
+        assert!(parse("run --param 15.row").is_err());
+        assert!(parse("run --day").is_err());
+        assert!(parse("list --day 1").is_err());
+        assert!(parse("solve").is_err());
+        assert!(parse("bench --runs 0").is_err());
+        assert!(parse("verify --answers").is_err());
+        assert!(parse("new-day 16").is_err());
+        assert!(parse("new-day x Title").is_err());
+        assert!(matches!(parse("--help"), Ok(Command::Help)));
+        assert!(matches!(parse("-d 9 -i -"), Ok(Command::Run(_))));
+    }
+}
@@ -0,0 +1,118 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{registry, Generated, Rng};
+
+// entry point of the fuzz targets, the parser of the day must turn any input into either the
+// parsed puzzle or an error, so any panic is a bug of the parser
+pub fn parse(day: u8, data: &[u8]) {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let registration = registry()
+        .into_iter()
+        .find(|registration| registration.day == day)
+        .unwrap_or_else(|| panic!("No puzzle registered for the day {day}"));
+    let _ = registration.from_input(input).parse();
+}
+
+// the characters the inputs are made of, and a few which break them
+const CHARACTERS: &[char] = &[
+    '0', '1', '9', ' ', ',', '-', '=', ':', '[', ']', '$', '\n', 'a', 'z', 'A', 'Z', 'x', 'é',
+];
+
+// the numbers just out of the range of the types, and the ones at their bounds which still
+// parse but overflow the arithmetic on them
+const NUMBERS: &[&str] = &[
+    "99999999999",
+    "2147483647",
+    "-2147483648",
+    "2000000000",
+    "-2000000000",
+    "4294967295",
+    "32767",
+    "-32768",
+];
+
+// the valid input damaged by a few random edits, the way a fuzzer starts from its corpus
+pub fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+    for _ in 0..rng.range(1..=4) {
+        let idx = rng.range(0..=chars.len());
+        match rng.range(0..=4) {
+            0 => chars.insert(idx, *rng.choose(CHARACTERS)),
+            1 if idx < chars.len() => {
+                let end = rng.range(idx..=chars.len().min(idx + 8));
+                chars.drain(idx..end);
+            }
+            2 if idx < chars.len() => chars[idx] = *rng.choose(CHARACTERS),
+            3 => {
+                // the number around the position is replaced as a whole along with its sign
+                let mut start = chars[..idx]
+                    .iter()
+                    .rposition(|c| !c.is_ascii_digit())
+                    .map_or(0, |start| start + 1);
+                if start > 0 && chars[start - 1] == '-' {
+                    start -= 1;
+                }
+                let end = chars[idx..]
+                    .iter()
+                    .position(|c| !c.is_ascii_digit())
+                    .map_or(chars.len(), |end| idx + end);
+                chars
+                    .splice(start..end, rng.choose(NUMBERS).chars())
+                    .for_each(drop);
+            }
+            _ => chars.truncate(idx),
+        }
+    }
+    chars.into_iter().collect()
+}
+
+// the inputs of the day which make the parser panic, mutated from the examples and from the
+// generated inputs
+pub fn panicking_inputs(day: u8, mutations: u64) -> Vec<String> {
+    let registration = registry()
+        .into_iter()
+        .find(|registration| registration.day == day)
+        .unwrap_or_else(|| panic!("No puzzle registered for the day {day}"));
+    let mut corpus: Vec<String> = registration
+        .examples
+        .iter()
+        .map(|example| String::from(example.input))
+        .collect();
+    corpus.extend((0..3).map(|seed| {
+        let Generated { input, .. } = (registration.generate)(&mut Rng::new(seed), 5);
+        input
+    }));
+
+    let mut rng = Rng::new(u64::from(day));
+    let mut inputs = vec![String::new()];
+    for input in &corpus {
+        inputs.extend((0..mutations).map(|_| mutate(&mut rng, input)));
+    }
+    inputs
+        .into_iter()
+        .filter(|input| {
+            panic::catch_unwind(AssertUnwindSafe(|| parse(day, input.as_bytes()))).is_err()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsers_never_panic() {
+        for registration in registry() {
+            let inputs = panicking_inputs(registration.day, 100);
+            assert!(
+                inputs.is_empty(),
+                "{}: the parser panicked for {} inputs, e.g.\n{}",
+                registration.name,
+                inputs.len(),
+                inputs[0]
+            );
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::ops::RangeInclusive;
+
+use num::PrimInt;
+
+// small seeded generator (splitmix64), the same seed always gives the same inputs
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn range<T: PrimInt>(&mut self, range: RangeInclusive<T>) -> T {
+        let (start, end) = (
+            range.start().to_i64().unwrap(),
+            range.end().to_i64().unwrap(),
+        );
+        assert!(start <= end, "empty range");
+        let span = (end - start) as u64 + 1;
+        T::from(start + (self.next_u64() % span) as i64).unwrap()
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.range(0..=len - 1)
+    }
+
+    // true in the given percentage of the calls
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.index(idx + 1));
+        }
+    }
+}
+
+// generated input of a puzzle together with the parameters it is meant to be solved with
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Generated {
+    pub input: String,
+    pub params: Vec<(&'static str, String)>,
+}
+
+impl From<String> for Generated {
+    fn from(input: String) -> Self {
+        Self {
+            input,
+            params: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_values() {
+        let values: Vec<u64> = (0..3).map(|_| Rng::new(7).next_u64()).collect();
+        assert!(values.windows(2).all(|pair| pair[0] == pair[1]));
+        assert_ne!(Rng::new(7).next_u64(), Rng::new(8).next_u64());
+
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!((-3..=5).contains(&rng.range(-3..=5)));
+            assert!(rng.index(4) < 4);
+        }
+        assert_eq!(10u8, rng.range(10..=10));
+
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!((0..20).collect::<Vec<_>>(), items);
+    }
+}
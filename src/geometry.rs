@@ -0,0 +1,325 @@
+use std::{
+    fmt, iter,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+use num::{PrimInt, Signed};
+
+// the directions on the screen, the y grows downwards the same way as the rows of the inputs
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+    // clockwise starting upwards
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    // the letters and the arrows the inputs use for the orthogonal directions
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            'U' | 'N' | '^' => Some(Direction::Up),
+            'R' | 'E' | '>' => Some(Direction::Right),
+            'D' | 'S' | 'v' => Some(Direction::Down),
+            'L' | 'W' | '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    // the change of the coordinates by a single step
+    fn delta(self) -> (i8, i8) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::UpRight => (1, -1),
+            Direction::Right => (1, 0),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (0, 1),
+            Direction::DownLeft => (-1, 1),
+            Direction::Left => (-1, 0),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    pub fn offset<T: Signed + Copy>(self) -> Point2<T> {
+        let unit = |delta: i8| match delta {
+            -1 => -T::one(),
+            1 => T::one(),
+            _ => T::zero(),
+        };
+        let (x, y) = self.delta();
+        Point2::new(unit(x), unit(y))
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        let (x, y) = self.delta();
+        x != 0 && y != 0
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn(4)
+    }
+
+    // by 90 degrees
+    pub fn turn_right(self) -> Direction {
+        self.turn(2)
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.turn(6)
+    }
+
+    // by the eighths of the full turn clockwise
+    fn turn(self, eighths: usize) -> Direction {
+        let idx = Direction::ALL.iter().position(|d| *d == self).unwrap();
+        Direction::ALL[(idx + eighths) % Direction::ALL.len()]
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: PrimInt> Point2<T> {
+    // the point next to this one, missing when it does not fit into the type
+    pub fn step(self, direction: Direction) -> Option<Self> {
+        let shift = |value: T, delta: i8| match delta {
+            -1 => value.checked_sub(&T::one()),
+            1 => value.checked_add(&T::one()),
+            _ => Some(value),
+        };
+        let (x, y) = direction.delta();
+        Some(Point2::new(shift(self.x, x)?, shift(self.y, y)?))
+    }
+
+    // the points sharing a side with this one, in the order of `Direction::ORTHOGONAL`
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .filter_map(move |direction| self.step(direction))
+    }
+
+    // the points sharing a side or a corner with this one, in the order of `Direction::ALL`
+    pub fn adjacent(self) -> impl Iterator<Item = Self> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(direction))
+    }
+
+    // the points further and further in the direction, not including this one
+    pub fn ray(self, direction: Direction) -> impl Iterator<Item = Self> {
+        iter::successors(self.step(direction), move |point| point.step(direction))
+    }
+
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y)
+    }
+
+    // the number of king moves between the points, the touching points are one apart
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+}
+
+impl<T: Signed + Copy> Point2<T> {
+    // the single step towards the direction of the point, including the diagonal ones
+    pub fn signum(self) -> Self {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Point2::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: PrimInt> Point3<T> {
+    // the points sharing a face with this one, the ones which do not fit into the type are
+    // left out
+    pub fn neighbours(self) -> impl Iterator<Item = Self> {
+        let Point3 { x, y, z } = self;
+        let one = T::one();
+        [
+            x.checked_sub(&one).map(|x| Point3::new(x, y, z)),
+            x.checked_add(&one).map(|x| Point3::new(x, y, z)),
+            y.checked_sub(&one).map(|y| Point3::new(x, y, z)),
+            y.checked_add(&one).map(|y| Point3::new(x, y, z)),
+            z.checked_sub(&one).map(|z| Point3::new(x, y, z)),
+            z.checked_add(&one).map(|z| Point3::new(x, y, z)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    pub fn manhattan(self, other: Self) -> T {
+        abs_diff(self.x, other.x) + abs_diff(self.y, other.y) + abs_diff(self.z, other.z)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Point3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+// works for the unsigned types as well
+fn abs_diff<T: PrimInt>(first: T, second: T) -> T {
+    first.max(second) - first.min(second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_moved() {
+        let point = Point2::new(3, -2);
+        assert_eq!(Point2::new(4, -4), point + Point2::new(1, -2));
+        assert_eq!(Point2::new(6, -4), point * 2);
+        assert_eq!(Point2::new(-1, 1), (Point2::new(0, 0) - point).signum());
+        assert_eq!(Point2::new(3, -3), point + Direction::Up.offset());
+        assert_eq!(7, point.manhattan(Point2::new(0, 2)));
+        assert_eq!(4, point.chebyshev(Point2::new(0, 2)));
+        assert_eq!("3,-2", point.to_string());
+
+        // the unsigned points stop at zero
+        let corner = Point2::<u8>::new(0, 1);
+        assert_eq!(None, corner.step(Direction::Left));
+        assert_eq!(
+            vec![Point2::new(0, 0), Point2::new(1, 1), Point2::new(0, 2)],
+            corner.neighbours().collect::<Vec<_>>()
+        );
+        assert_eq!(5, corner.adjacent().count());
+        assert_eq!(
+            vec![Point2::new(0, 0)],
+            corner.ray(Direction::Up).collect::<Vec<_>>()
+        );
+        assert_eq!(2, Point2::<u8>::new(4, 1).manhattan(Point2::new(3, 2)));
+    }
+
+    #[test]
+    fn directions_turned() {
+        assert_eq!(Some(Direction::Left), Direction::from_char('<'));
+        assert_eq!(None, Direction::from_char('X'));
+        assert_eq!(Direction::Right, Direction::Up.turn_right());
+        assert_eq!(Direction::DownRight, Direction::UpRight.turn_right());
+        assert_eq!(Direction::Left, Direction::Up.turn_left());
+        assert_eq!(Direction::UpLeft, Direction::DownRight.opposite());
+        assert!(Direction::DownLeft.is_diagonal());
+        assert!(!Direction::Down.is_diagonal());
+    }
+
+    #[test]
+    fn points_in_space() {
+        let point = Point3::<u16>::new(0, 5, 2);
+        assert_eq!(5, point.neighbours().count());
+        assert_eq!(4, point.manhattan(Point3::new(1, 3, 3)));
+        assert_eq!(Point3::new(1, 6, 3), point + Point3::new(1, 1, 1));
+        assert_eq!("0,5,2", point.to_string());
+    }
+}
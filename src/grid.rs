@@ -0,0 +1,333 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{parse::InputLine, Direction, ParseError, Point2};
+
+// the cells are stored row by row, the x is the column and the y the row of the input
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cell: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![cell; width * height],
+        }
+    }
+
+    // reads the lines as the rows of the grid, every row must be as long as the first one, the
+    // cell gets the line and the byte offset of the char for its errors
+    pub fn parse<'a>(
+        lines: impl Iterator<Item = InputLine<'a>>,
+        cells: &str,
+        mut cell: impl FnMut(&InputLine, usize, char) -> Result<T, ParseError>,
+    ) -> Result<Self, ParseError> {
+        let mut grid = Grid {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        };
+        for line in lines {
+            let width = line.text.chars().count();
+            if grid.height == 0 {
+                grid.width = width;
+            } else if width != grid.width {
+                let offset = line
+                    .text
+                    .char_indices()
+                    .nth(grid.width)
+                    .map_or(line.text.len(), |(offset, _)| offset);
+                let expected = format!("a row of {} {cells}", grid.width);
+                return Err(line.error(offset, &expected));
+            }
+            for (offset, c) in line.text.char_indices() {
+                grid.cells.push(cell(&line, offset, c)?);
+            }
+            grid.height += 1;
+        }
+        Ok(grid)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, position: Point2<usize>) -> bool {
+        position.x < self.width && position.y < self.height
+    }
+
+    // the cells in the first or the last row or column
+    pub fn is_edge(&self, position: Point2<usize>) -> bool {
+        self.contains(position)
+            && (position.x == 0
+                || position.y == 0
+                || position.x == self.width - 1
+                || position.y == self.height - 1)
+    }
+
+    pub fn get(&self, position: Point2<usize>) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[position.y * self.width + position.x])
+    }
+
+    pub fn get_mut(&mut self, position: Point2<usize>) -> Option<&mut T> {
+        match self.contains(position) {
+            true => Some(&mut self.cells[position.y * self.width + position.x]),
+            false => None,
+        }
+    }
+
+    // row by row, from the left to the right
+    pub fn positions(&self) -> impl Iterator<Item = Point2<usize>> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |idx| Point2::new(idx % width, idx / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // the rows and the columns outside of the grid are empty
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> {
+        let row = match y < self.height {
+            true => &self.cells[y * self.width..(y + 1) * self.width],
+            false => &[],
+        };
+        row.iter()
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = match x < self.width {
+            true => self.height,
+            false => 0,
+        };
+        self.cells
+            .iter()
+            .skip(x)
+            .step_by(self.width.max(1))
+            .take(height)
+    }
+
+    // the cells further and further in the direction up to the edge, not including the
+    // position itself, the diagonal directions walk along the diagonals
+    pub fn ray(
+        &self,
+        position: Point2<usize>,
+        direction: Direction,
+    ) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        position
+            .ray(direction)
+            .map_while(|position| Some((position, self.get(position)?)))
+    }
+
+    // the cells sharing a side with the position
+    pub fn neighbours(&self, position: Point2<usize>) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        position
+            .neighbours()
+            .filter_map(|position| Some((position, self.get(position)?)))
+    }
+
+    // the cells sharing a side or a corner with the position
+    pub fn adjacent(&self, position: Point2<usize>) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        position
+            .adjacent()
+            .filter_map(|position| Some((position, self.get(position)?)))
+    }
+
+    // the rows become the columns
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.rearranged(self.height, self.width, |Point2 { x, y }| Point2::new(y, x))
+    }
+
+    // by 90 degrees clockwise, the first row becomes the last column
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.rearranged(height, self.width, |Point2 { x, y }| {
+            Point2::new(y, height - 1 - x)
+        })
+    }
+
+    // by 90 degrees counterclockwise, the first row becomes the first column
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.rearranged(self.height, width, |Point2 { x, y }| {
+            Point2::new(width - 1 - y, x)
+        })
+    }
+
+    // the cell of the new grid is taken from the position of this grid given by `source`
+    fn rearranged(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(Point2<usize>) -> Point2<usize>,
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..width * height)
+            .map(|idx| self[source(Point2::new(idx % width, idx / width))].clone())
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T> Index<Point2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Point2<usize>) -> &T {
+        self.get(position)
+            .unwrap_or_else(|| panic!("{position} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Point2<usize>> for Grid<T> {
+    fn index_mut(&mut self, position: Point2<usize>) -> &mut T {
+        self.get_mut(position)
+            .unwrap_or_else(|| panic!("{position} is outside of the grid"))
+    }
+}
+
+// the cells of a row follow each other without any separator, the rows are on separate lines
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().take(self.height).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_parsed() {
+        let input = "123\n456";
+        let lines = input
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| InputLine::new(1, idx, line));
+        let grid = Grid::parse(lines, "digits", |line, offset, c| {
+            c.to_digit(10).ok_or_else(|| line.error(offset, "a digit"))
+        })
+        .unwrap();
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(input, grid.to_string());
+
+        let lines = "123\n4x6"
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| InputLine::new(1, idx, line));
+        let error = Grid::parse(lines, "digits", |line, offset, c| {
+            c.to_digit(10).ok_or_else(|| line.error(offset, "a digit"))
+        })
+        .unwrap_err();
+        assert_eq!((2, 2), (error.line, error.column));
+
+        let lines = "123\n4567"
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| InputLine::new(1, idx, line));
+        let error = Grid::parse(lines, "digits", |_, _, c| Ok(c)).unwrap_err();
+        assert_eq!((2, 4), (error.line, error.column));
+        assert_eq!("a row of 3 digits", error.expected);
+    }
+
+    #[test]
+    fn cells_walked() {
+        let grid = Grid {
+            width: 3,
+            height: 3,
+            cells: (1..=9).collect(),
+        };
+        assert_eq!(Some(&6), grid.get(Point2::new(2, 1)));
+        assert_eq!(None, grid.get(Point2::new(3, 1)));
+        assert_eq!(vec![&4, &5, &6], grid.row(1).collect::<Vec<_>>());
+        assert_eq!(vec![&2, &5, &8], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(
+            vec![&5, &9],
+            grid.ray(Point2::new(0, 0), Direction::DownRight)
+                .map(|(_, cell)| cell)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&2, &4],
+            grid.neighbours(Point2::new(0, 0))
+                .map(|(_, cell)| cell)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, grid.row(3).count());
+        assert_eq!(0, grid.column(3).count());
+        assert_eq!(8, grid.adjacent(Point2::new(1, 1)).count());
+        assert!(grid.is_edge(Point2::new(2, 1)));
+        assert!(!grid.is_edge(Point2::new(1, 1)));
+    }
+
+    #[test]
+    fn empty_grid() {
+        let grid: Grid<char> = Grid::new(0, 2, '.');
+        assert!(grid.is_empty());
+        assert_eq!(0, grid.row(0).count());
+        assert_eq!(0, grid.column(0).count());
+        assert_eq!(0, grid.positions().count());
+    }
+
+    #[test]
+    fn grid_turned() {
+        let grid = Grid {
+            width: 3,
+            height: 2,
+            cells: vec!['a', 'b', 'c', 'd', 'e', 'f'],
+        };
+        assert_eq!("ad\nbe\ncf", grid.transpose().to_string());
+        assert_eq!("da\neb\nfc", grid.rotate_right().to_string());
+        assert_eq!("cf\nbe\nad", grid.rotate_left().to_string());
+        assert_eq!(grid, grid.rotate_right().rotate_left());
+
+        let mut grid = Grid::new(2, 1, '.');
+        grid[Point2::new(1, 0)] = '#';
+        assert_eq!(".#", grid.to_string());
+    }
+}
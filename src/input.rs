@@ -0,0 +1,77 @@
+use std::{
+    env, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+// overrides the default directory with the inputs of the puzzles
+pub const INPUTS_VAR: &str = "CODE_ADVENT_INPUTS";
+
+enum InputFile {
+    Path(PathBuf),
+    Stdin,
+}
+
+pub struct InputSource {
+    dir: PathBuf,
+    // replaces the input of the (single) selected puzzle
+    file: Option<InputFile>,
+}
+
+impl InputSource {
+    // the directory given on the command line takes precedence over the environment variable
+    pub fn new(dir: Option<&str>, file: Option<&str>) -> Self {
+        let dir = dir
+            .map(PathBuf::from)
+            .or_else(|| env::var_os(INPUTS_VAR).map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from("inputs"));
+        let file = file.map(|file| match file {
+            "-" => InputFile::Stdin,
+            path => InputFile::Path(PathBuf::from(path)),
+        });
+        Self { dir, file }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn read(&self, file_name: &str) -> Result<String, String> {
+        match &self.file {
+            Some(InputFile::Stdin) => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|error| format!("Not able to read the input from stdin: {error}"))?;
+                Ok(input)
+            }
+            Some(InputFile::Path(path)) => read_file(path),
+            None => read_file(&self.dir.join(file_name)),
+        }
+    }
+}
+
+pub(crate) fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path)
+        .map_err(|error| format!("Not able to read the input {}: {error}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_file_replaces_the_directory() {
+        let path = env::temp_dir().join("code_advent_2022_input.txt");
+        fs::write(&path, "R 4").unwrap();
+
+        let source = InputSource::new(Some("missing"), path.to_str());
+        assert_eq!(Ok(String::from("R 4")), source.read("9.txt"));
+        fs::remove_file(&path).unwrap();
+
+        let error = InputSource::new(Some("missing"), None)
+            .read("9.txt")
+            .unwrap_err();
+        assert!(error.contains(&format!("{}", Path::new("missing").join("9.txt").display())));
+    }
+}
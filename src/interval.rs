@@ -0,0 +1,287 @@
+use num::PrimInt;
+
+// closed interval, both of the ends belong to it
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    // the number of the values within the interval, none when it does not fit into the type,
+    // like for the whole range of it
+    pub fn len(&self) -> Option<T> {
+        if self.is_empty() {
+            return Some(T::zero());
+        }
+        self.end.checked_sub(&self.start)?.checked_add(&T::one())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start > self.end
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.start <= other.start && self.end >= other.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.overlaps(other)
+            .then(|| Interval::new(self.start.max(other.start), self.end.min(other.end)))
+    }
+}
+
+// the intervals are kept sorted, the overlapping and the adjacent ones are merged into one
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // the reversed intervals hold no values, they would break the order of the set
+        if interval.is_empty() {
+            return;
+        }
+        // the intervals ending right before the start of the new one touch it as well
+        let first = self.intervals.partition_point(|other| {
+            other
+                .end
+                .checked_add(&T::one())
+                .is_some_and(|next| next < interval.start)
+        });
+        let last = self.intervals.partition_point(|other| {
+            interval
+                .end
+                .checked_add(&T::one())
+                .is_none_or(|next| other.start <= next)
+        });
+        let merged = match first < last {
+            true => Interval::new(
+                interval.start.min(self.intervals[first].start),
+                interval.end.max(self.intervals[last - 1].end),
+            ),
+            false => interval,
+        };
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let first = self
+            .intervals
+            .partition_point(|other| other.end < interval.start);
+        let last = self
+            .intervals
+            .partition_point(|other| other.start <= interval.end);
+        // only the parts sticking out of the removed interval are left
+        let mut rest = Vec::new();
+        if first < last {
+            let (head, tail) = (self.intervals[first], self.intervals[last - 1]);
+            if head.start < interval.start {
+                rest.push(Interval::new(head.start, interval.start - T::one()));
+            }
+            if tail.end > interval.end {
+                rest.push(Interval::new(interval.end + T::one(), tail.end));
+            }
+        }
+        self.intervals.splice(first..last, rest);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for interval in other.iter() {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.iter()
+            .flat_map(|interval| {
+                other
+                    .iter()
+                    .filter_map(move |other| interval.intersection(other))
+            })
+            .collect()
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for interval in other.iter() {
+            difference.remove(*interval);
+        }
+        difference
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.containing(value)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    // the merged intervals do not touch, so the whole interval must be within a single one
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        self.containing(interval.start)
+            .is_some_and(|other| other.contains_interval(interval))
+    }
+
+    // the first interval not ending before the value
+    fn containing(&self, value: T) -> Option<&Interval<T>> {
+        let idx = self.intervals.partition_point(|other| other.end < value);
+        self.intervals.get(idx)
+    }
+
+    // the total number of the values within the intervals, none when it does not fit into the
+    // type
+    pub fn len(&self) -> Option<T> {
+        self.iter()
+            .try_fold(T::zero(), |len, interval| len.checked_add(&interval.len()?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    // the values missing between the first and the last interval
+    pub fn gaps(&self) -> impl Iterator<Item = Interval<T>> + '_ {
+        self.intervals
+            .windows(2)
+            .map(|pair| Interval::new(pair[0].end + T::one(), pair[1].start - T::one()))
+    }
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(interval);
+        set
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(intervals: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in intervals {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    fn intervals(set: &IntervalSet<i32>) -> Vec<(i32, i32)> {
+        set.iter()
+            .map(|interval| (interval.start, interval.end))
+            .collect()
+    }
+
+    #[test]
+    fn intervals_merged() {
+        let mut intervals_set = set(&[(10, 12), (1, 3), (5, 6)]);
+        assert_eq!(vec![(1, 3), (5, 6), (10, 12)], intervals(&intervals_set));
+        // the adjacent intervals become one
+        intervals_set.insert(Interval::new(4, 4));
+        assert_eq!(vec![(1, 6), (10, 12)], intervals(&intervals_set));
+        intervals_set.insert(Interval::new(0, 11));
+        assert_eq!(vec![(0, 12)], intervals(&intervals_set));
+        assert_eq!(Some(13), intervals_set.len());
+
+        intervals_set.remove(Interval::new(3, 5));
+        assert_eq!(vec![(0, 2), (6, 12)], intervals(&intervals_set));
+        assert_eq!(
+            vec![Interval::new(3, 5)],
+            intervals_set.gaps().collect::<Vec<_>>()
+        );
+        assert!(intervals_set.contains(6));
+        assert!(!intervals_set.contains(4));
+        assert!(intervals_set.contains_interval(&Interval::new(7, 12)));
+        assert!(!intervals_set.contains_interval(&Interval::new(2, 6)));
+
+        let mut bounded = IntervalSet::from(Interval::new(250u8, 255));
+        bounded.insert(Interval::new(0, 249));
+        assert_eq!(
+            vec![Interval::new(0, 255)],
+            bounded.iter().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn sets_combined() {
+        let first = set(&[(0, 4), (8, 10)]);
+        let second = set(&[(3, 8), (12, 12)]);
+        assert_eq!(vec![(0, 10), (12, 12)], intervals(&first.union(&second)));
+        assert_eq!(
+            vec![(3, 4), (8, 8)],
+            intervals(&first.intersection(&second))
+        );
+        assert_eq!(vec![(0, 2), (9, 10)], intervals(&first.difference(&second)));
+        assert!(first.difference(&first).is_empty());
+    }
+
+    #[test]
+    fn intervals_compared() {
+        let interval = Interval::new(2, 8);
+        assert!(interval.contains_interval(&Interval::new(3, 7)));
+        assert!(!interval.contains_interval(&Interval::new(3, 9)));
+        assert!(interval.overlaps(&Interval::new(8, 9)));
+        assert!(!interval.overlaps(&Interval::new(9, 9)));
+        assert_eq!(
+            Some(Interval::new(6, 8)),
+            interval.intersection(&Interval::new(6, 10))
+        );
+        assert_eq!(Some(7), interval.len());
+        assert_eq!(None, Interval::new(i32::MIN, i32::MAX).len());
+        assert_eq!(Some(u8::MAX), Interval::new(1u8, 255).len());
+    }
+
+    #[test]
+    fn reversed_intervals_ignored() {
+        let mut intervals_set = set(&[(1, 3), (8, 5), (10, 12)]);
+        assert_eq!(vec![(1, 3), (10, 12)], intervals(&intervals_set));
+        intervals_set.insert(Interval::new(12, 0));
+        intervals_set.remove(Interval::new(11, 2));
+        assert_eq!(vec![(1, 3), (10, 12)], intervals(&intervals_set));
+        assert!(IntervalSet::from(Interval::new(2, 1)).is_empty());
+        assert_eq!(Some(0), Interval::new(2, 1).len());
+    }
+}
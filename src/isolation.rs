@@ -0,0 +1,83 @@
+use std::{
+    cell::{Cell, RefCell},
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use code_advent_2022::{Answer, Part, Solution};
+
+thread_local! {
+    // set while an isolated function runs on the thread, its panics are recorded instead
+    // of being printed by the default hook
+    static ISOLATED: Cell<bool> = const { Cell::new(false) };
+    static PANIC_MESSAGE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+// runs the function so that a panic within it turns into an error instead of aborting the run
+pub fn isolate<T, F: FnOnce() -> Result<T, String>>(f: F) -> Result<T, String> {
+    HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !ISOLATED.get() {
+                return default_hook(info);
+            }
+            let message = match info.payload().downcast_ref::<&str>() {
+                Some(message) => String::from(*message),
+                None => match info.payload().downcast_ref::<String>() {
+                    Some(message) => message.clone(),
+                    None => String::from("unknown cause"),
+                },
+            };
+            let location = match info.location() {
+                Some(location) => format!(" at {location}"),
+                None => String::new(),
+            };
+            PANIC_MESSAGE.set(Some(format!("Panicked{location}: {message}")));
+        }));
+    });
+
+    let was_isolated = ISOLATED.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    ISOLATED.set(was_isolated);
+
+    result.unwrap_or_else(|_| {
+        Err(PANIC_MESSAGE
+            .take()
+            .unwrap_or_else(|| String::from("Panicked")))
+    })
+}
+
+pub fn solve(puzzle: &dyn Solution, part: Part) -> Result<Answer, String> {
+    isolate(|| puzzle.solution(part).map_err(|error| error.to_string()))
+}
+
+pub fn parse(puzzle: &dyn Solution) -> Result<String, String> {
+    isolate(|| puzzle.parse().map_err(|error| error.to_string()))
+}
+
+pub fn reparse(puzzle: &dyn Solution) -> Result<(), String> {
+    isolate(|| puzzle.reparse().map_err(|error| error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_turned_into_error() {
+        assert_eq!(Ok(3), isolate(|| Ok(1 + 2)));
+        assert_eq!(
+            Err(String::from("wrong")),
+            isolate::<(), _>(|| Err(String::from("wrong")))
+        );
+
+        let error = isolate::<(), _>(|| panic!("unexpected input {}", 15)).unwrap_err();
+        assert!(error.starts_with("Panicked at src/isolation.rs:"));
+        assert!(error.ends_with(": unexpected input 15"));
+
+        let error = isolate(|| Ok(Vec::<u8>::new()[1])).unwrap_err();
+        assert!(error.contains("index out of bounds"));
+    }
+}
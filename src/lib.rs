@@ -0,0 +1,312 @@
+use std::{error::Error, fmt, path::Path, sync::OnceLock};
+
+pub use answer::Answer;
+pub use generate::{Generated, Rng};
+pub use geometry::{Direction, Point2, Point3};
+pub use grid::Grid;
+pub use input::InputSource;
+pub use interval::{Interval, IntervalSet};
+pub use ocr::{Framebuffer, OcrError};
+pub use params::{configure, read_config, Override, Parameters};
+pub use parse::ParseError;
+pub use puzzles::registry;
+pub use visualize::{Frame, Visualize};
+
+use parse::InputLine;
+
+mod answer;
+#[cfg(any(test, feature = "fuzz"))]
+pub mod fuzz;
+mod generate;
+pub mod geometry;
+pub mod grid;
+mod input;
+pub mod interval;
+mod ocr;
+#[macro_use]
+mod params;
+mod parse;
+#[cfg(test)]
+mod property;
+pub mod puzzles;
+pub mod visualize;
+
+pub struct PuzzleInfo {
+    day: u8,
+    name: String,
+    input: String,
+}
+
+impl PuzzleInfo {
+    pub fn new(day: u8, name: &str, input: &str) -> Self {
+        Self {
+            day,
+            name: String::from(name),
+            input: String::from(input),
+        }
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = InputLine<'_>> {
+        self.input
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| InputLine::new(self.day, idx, line))
+    }
+
+    pub fn end_of_input_error(&self, expected: &str) -> ParseError {
+        ParseError {
+            day: self.day,
+            line: self.input.lines().count() + 1,
+            column: 1,
+            expected: String::from(expected),
+            found: String::from("end of input"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Part {
+    First,
+    Second,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::First, Part::Second];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::First => 1,
+            Part::Second => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::First => write!(f, "first"),
+            Part::Second => write!(f, "second"),
+        }
+    }
+}
+
+// the puzzles are solved on worker threads
+pub trait Solution: Send + Sync {
+    fn puzzle_info(&self) -> &PuzzleInfo;
+    // parses the input and keeps the model for solving the parts, the summary tells what was
+    // parsed
+    fn parse(&self) -> Result<String, ParseError>;
+    // parses the input again without keeping the model, so the parsing can be timed on its own
+    fn reparse(&self) -> Result<(), ParseError>;
+    fn solution(&self, part: Part) -> Result<Answer, SolveError>;
+    // the puzzles without any parameters keep the default
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        None
+    }
+    // draws the frames of solving the part, missing for the puzzles which draw nothing
+    fn visualize(&self, part: Part, sink: &mut dyn FnMut(&Frame))
+        -> Option<Result<(), ParseError>>;
+}
+
+// the puzzle reads its input into a typed model, both parts are solved from the same model
+pub trait Puzzle: Send + Sync {
+    type Model: Send + Sync;
+
+    fn puzzle_info(&self) -> &PuzzleInfo;
+    fn parse(&self) -> Result<Self::Model, ParseError>;
+    // tells what was parsed, e.g. the number of elves
+    fn summary(&self, model: &Self::Model) -> String;
+    fn solve(&self, model: &Self::Model, part: Part) -> Result<Answer, SolveError>;
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        None
+    }
+    // the spatial simulations draw themselves
+    fn visualization(&self) -> Option<&dyn Visualize<Model = Self::Model>> {
+        None
+    }
+}
+
+// the puzzle with its input parsed on the first use, the model is shared by both parts
+pub struct Parsed<P: Puzzle> {
+    puzzle: P,
+    model: OnceLock<Result<P::Model, ParseError>>,
+}
+
+impl<P: Puzzle> Parsed<P> {
+    pub fn new(puzzle: P) -> Self {
+        Self {
+            puzzle,
+            model: OnceLock::new(),
+        }
+    }
+
+    fn model(&self) -> Result<&P::Model, ParseError> {
+        self.model
+            .get_or_init(|| self.puzzle.parse())
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+}
+
+impl<P: Puzzle> Solution for Parsed<P> {
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        self.puzzle.puzzle_info()
+    }
+
+    fn parse(&self) -> Result<String, ParseError> {
+        Ok(self.puzzle.summary(self.model()?))
+    }
+
+    fn reparse(&self) -> Result<(), ParseError> {
+        self.puzzle.parse().map(|_| ())
+    }
+
+    fn solution(&self, part: Part) -> Result<Answer, SolveError> {
+        self.puzzle.solve(self.model()?, part)
+    }
+
+    fn visualize(
+        &self,
+        part: Part,
+        sink: &mut dyn FnMut(&Frame),
+    ) -> Option<Result<(), ParseError>> {
+        let visualization = self.puzzle.visualization()?;
+        Some(
+            self.model()
+                .map(|model| visualization.frames(model, part, sink)),
+        )
+    }
+
+    // the model is parsed again after a parameter changes, it may depend on it
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        self.model = OnceLock::new();
+        self.puzzle.parameters()
+    }
+}
+
+// the input of the puzzle may not be valid, besides that the answer itself may be drawn in
+// block letters that are not recognized, or a valid input may have no answer at all
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    Parse(ParseError),
+    Ocr(OcrError),
+    NoSolution(String),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Parse(error) => write!(f, "{error}"),
+            SolveError::Ocr(error) => write!(f, "{error}"),
+            SolveError::NoSolution(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(error: ParseError) -> Self {
+        SolveError::Parse(error)
+    }
+}
+
+impl From<OcrError> for SolveError {
+    fn from(error: OcrError) -> Self {
+        SolveError::Ocr(error)
+    }
+}
+
+pub struct Registration {
+    pub day: u8,
+    pub name: &'static str,
+    // the name of the file within the inputs directory
+    pub input: &'static str,
+    pub examples: &'static [Example],
+    // generates a valid input of roughly the given size from the seed
+    pub generate: fn(&mut Rng, usize) -> Generated,
+    build: fn(PuzzleInfo) -> Box<dyn Solution>,
+}
+
+impl Registration {
+    pub fn load(&self, source: &InputSource) -> Result<Box<dyn Solution>, String> {
+        Ok(self.from_input(&source.read(self.input)?))
+    }
+
+    pub fn from_input(&self, input: &str) -> Box<dyn Solution> {
+        self.build_named(self.name, input)
+    }
+
+    // the puzzle is named after the file, so the inputs of a batch can be told apart
+    pub fn load_file(&self, path: &Path) -> Result<Box<dyn Solution>, String> {
+        let input = input::read_file(path)?;
+        let file_name = path.file_name().unwrap_or(path.as_os_str());
+        let name = format!("{} ({})", self.name, file_name.to_string_lossy());
+        Ok(self.build_named(&name, &input))
+    }
+
+    // the puzzles built from the examples are named after them when there are more of them
+    pub fn examples(&self) -> impl Iterator<Item = (Box<dyn Solution>, &'static Example)> + '_ {
+        self.examples.iter().enumerate().map(|(idx, example)| {
+            let name = match self.examples.len() {
+                1 => format!("{} (example)", self.name),
+                _ => format!("{} (example {})", self.name, idx + 1),
+            };
+            let mut puzzle = self.build_named(&name, example.input);
+            // the parameters of the examples are fixed, an invalid one is a mistake in the code
+            for (name, value) in example.params {
+                params::set_parameter(puzzle.as_mut(), name, value)
+                    .unwrap_or_else(|error| panic!("{error}"));
+            }
+            (puzzle, example)
+        })
+    }
+
+    // the generated puzzle is named after the seed and the size, so it can be generated again
+    pub fn generated(&self, seed: u64, size: usize) -> Box<dyn Solution> {
+        let generated = (self.generate)(&mut Rng::new(seed), size);
+        let name = format!("{} (seed {seed}, size {size})", self.name);
+        self.build_generated(&name, &generated)
+    }
+
+    pub fn build_generated(&self, name: &str, generated: &Generated) -> Box<dyn Solution> {
+        let mut puzzle = self.build_named(name, &generated.input);
+        // the generators set only the parameters they know
+        for (name, value) in &generated.params {
+            params::set_parameter(puzzle.as_mut(), name, value)
+                .unwrap_or_else(|error| panic!("{error}"));
+        }
+        puzzle
+    }
+
+    fn build_named(&self, name: &str, input: &str) -> Box<dyn Solution> {
+        (self.build)(PuzzleInfo::new(self.day, name, input))
+    }
+}
+
+// an official example from the puzzle description
+pub struct Example {
+    pub input: &'static str,
+    // the expected answers of both parts, missing when the example does not cover the part
+    pub answers: [Option<&'static str>; 2],
+    // the parameters the example differs in from the real puzzle
+    pub params: &'static [(&'static str, &'static str)],
+}
+
+impl Example {
+    pub fn answer(&self, part: Part) -> Option<&'static str> {
+        self.answers[part.number() as usize - 1]
+    }
+}
@@ -1,51 +1,309 @@
-use std::fs;
+use std::{env, process, time::Instant};
 
-use puzzles::{
-    eighth_puzzle::EighthPuzzle, eleventh_puzzle::EleventhPuzzle, fifth_puzzle::FifthPuzzle,
-    first_puzzle::FirstPuzzle, fourth_puzzle::FourthPuzzle, ninth_puzzle::NinthPuzzle,
-    second_puzzle::SecondPuzzle, seventh_puzzle::SeventhPuzzle, sixth_puzzle::SixthPuzzle,
-    tenth_puzzle::TenthPuzzle, third_puzzle::ThirdPuzzle, thirteenth_puzzle::ThirteenthPuzzle,
-    twelfth_puzzle::TwelfthPuzzle, fourteenth_puzzle::FourteenthPuzzle, fifteenth_puzzle::FifteenthPuzzle,
-};
+use cli::{Command, GenerateOptions, RunOptions, Selection, USAGE};
+use code_advent_2022::{configure, registry, Example, Part, Registration, Rng, Solution};
+use report::{Outcome, PartReport};
 
-mod puzzles;
-mod util;
+mod animation;
+mod batch;
+mod bench;
+mod cli;
+mod isolation;
+mod pool;
+mod report;
+mod scaffold;
+mod verify;
 
-pub struct PuzzleInfo {
-    name: String,
-    input: String,
+// reads the inputs of the selected puzzles only, so the others do not need to be present
+fn load(selection: &Selection) -> Result<Vec<Box<dyn Solution>>, String> {
+    let registry = registry();
+    selection.check_days(&registry)?;
+    let source = selection.input_source()?;
+    let overrides = selection.overrides()?;
+    registry
+        .iter()
+        .filter(|registration| selection.includes_day(registration.day))
+        .map(|registration| {
+            let mut puzzle = registration.load(&source)?;
+            configure(puzzle.as_mut(), &overrides)?;
+            Ok(puzzle)
+        })
+        .collect()
 }
 
-impl PuzzleInfo {
-    pub fn new(name: &str, file_path: &str) -> Self {
-        Self {
-            name: String::from(name),
-            input: fs::read_to_string(file_path).expect("Not able to read the file"),
+// the official examples of the selected puzzles, the parts without an expected answer are skipped
+fn load_examples(selection: &Selection) -> Result<Vec<Task>, String> {
+    let registry = registry();
+    selection.check_days(&registry)?;
+    let overrides = selection.overrides()?;
+    registry
+        .iter()
+        .filter(|registration| selection.includes_day(registration.day))
+        .flat_map(|registration| registration.examples())
+        .map(|(mut puzzle, example)| {
+            configure(puzzle.as_mut(), &overrides)?;
+            // the answers of the example do not hold once its parameters are overridden
+            let day = puzzle.puzzle_info().day();
+            let checked = !overrides.iter().any(|parameter| parameter.day == day);
+            Ok(Task {
+                puzzle,
+                example: Some(example),
+                checked,
+            })
+        })
+        .collect()
+}
+
+// a puzzle to solve together with the example it was built from, its answers are compared
+// with the expected ones when checked
+struct Task {
+    puzzle: Box<dyn Solution>,
+    example: Option<&'static Example>,
+    checked: bool,
+}
+
+fn run(tasks: &[Task], options: &RunOptions) -> Result<(), String> {
+    let selected_parts = options.selection.parts();
+    let reports: Vec<PartReport> = pool::map(tasks, options.jobs, |task| {
+        solve(
+            task.puzzle.as_ref(),
+            task.example,
+            task.checked,
+            &selected_parts,
+        )
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+    report::print(options.format, &reports);
+    report::print_summary(options.format, &reports);
+
+    let failures = reports
+        .iter()
+        .filter(|report| matches!(report.outcome, Outcome::Failed(_)))
+        .count();
+    match failures {
+        0 => Ok(()),
+        _ => Err(format!("{failures} part(s) could not be solved")),
+    }
+}
+
+// every part is timed on its own, the parts which are not selected are skipped
+fn solve<'a>(
+    puzzle: &'a dyn Solution,
+    example: Option<&Example>,
+    checked: bool,
+    selected_parts: &[Part],
+) -> Vec<PartReport<'a>> {
+    let puzzle_info = puzzle.puzzle_info();
+    // the model is built before the timers start, so the first part does not count the parsing
+    let parsed = isolation::parse(puzzle).map(|_| ());
+    Part::ALL
+        .into_iter()
+        .map(|part| {
+            let expected = example.map(|example| example.answer(part));
+            let (outcome, elapsed) = if selected_parts.contains(&part) && expected != Some(None) {
+                let start = Instant::now();
+                let solved = parsed.clone().and_then(|_| isolation::solve(puzzle, part));
+                let outcome = match (solved, expected.flatten()) {
+                    (Ok(answer), Some(expected)) if checked && answer.to_string() != expected => {
+                        Outcome::Failed(format!("Expected {expected}, found {answer}"))
+                    }
+                    (Ok(answer), _) => Outcome::Solved(answer),
+                    (Err(error), _) => Outcome::Failed(error),
+                };
+                (outcome, Some(start.elapsed()))
+            } else {
+                (Outcome::Skipped, None)
+            };
+            PartReport {
+                name: puzzle_info.name(),
+                day: puzzle_info.day(),
+                part,
+                outcome,
+                elapsed,
+            }
+        })
+        .collect()
+}
+
+// parses the selected inputs without solving them, a missing input does not stop the others
+fn check(selection: &Selection) -> Result<(), String> {
+    let registry = registry();
+    selection.check_days(&registry)?;
+    let source = selection.input_source()?;
+    let overrides = selection.overrides()?;
+
+    let mut invalid = 0;
+    for registration in registry
+        .iter()
+        .filter(|registration| selection.includes_day(registration.day))
+    {
+        let summary = registration.load(&source).and_then(|mut puzzle| {
+            configure(puzzle.as_mut(), &overrides)?;
+            isolation::parse(puzzle.as_ref())
+        });
+        match summary {
+            Ok(summary) => println!("{}: {summary}", registration.name),
+            Err(error) => {
+                eprintln!("{}: Invalid input. {error}", registration.name);
+                invalid += 1;
+            }
         }
     }
+
+    match invalid {
+        0 => Ok(()),
+        _ => Err(format!("{invalid} input(s) are not valid")),
+    }
 }
-pub trait Solution {
-    fn solution(&self);
+
+fn generate(options: &GenerateOptions) -> Result<(), String> {
+    let registration = registry()
+        .into_iter()
+        .find(|registration| registration.day == options.day)
+        .ok_or_else(|| format!("There is no puzzle registered for day {}", options.day))?;
+    let generated = (registration.generate)(&mut Rng::new(options.seed), options.size);
+    println!("{}", generated.input);
+    if !generated.params.is_empty() {
+        let params: Vec<String> = generated
+            .params
+            .iter()
+            .map(|(name, value)| format!("--param {}.{name}={value}", options.day))
+            .collect();
+        eprintln!("Parameters: {}", params.join(" "));
+    }
+    Ok(())
+}
+
+fn list(registry: &[Registration]) {
+    for registration in registry {
+        // the defaults do not depend on the input
+        let mut puzzle = registration.from_input("");
+        let params = match puzzle.parameters() {
+            Some(parameters) => {
+                let values: Vec<String> = parameters
+                    .values()
+                    .iter()
+                    .map(|(name, value)| format!("{}.{name}={value}", registration.day))
+                    .collect();
+                format!(" ({})", values.join(", "))
+            }
+            None => String::new(),
+        };
+        println!("{:>2}: {}{params}", registration.day, registration.name);
+    }
 }
 
 fn main() {
-    let puzzles: Vec<Box<dyn Solution>> = vec![
-        Box::new(FirstPuzzle::new()),
-        Box::new(SecondPuzzle::new()),
-        Box::new(ThirdPuzzle::new()),
-        Box::new(FourthPuzzle::new()),
-        Box::new(FifthPuzzle::new()),
-        Box::new(SixthPuzzle::new()),
-        Box::new(SeventhPuzzle::new()),
-        Box::new(EighthPuzzle::new()),
-        Box::new(NinthPuzzle::new()),
-        Box::new(TenthPuzzle::new()),
-        Box::new(EleventhPuzzle::new()),
-        Box::new(TwelfthPuzzle::new()),
-        Box::new(ThirteenthPuzzle::new()),
-        Box::new(FourteenthPuzzle::new()),
-        Box::new(FifteenthPuzzle::new()),
-    ];
-
-    puzzles.iter().for_each(|puzzle| puzzle.solution());
+    let command = Command::from_args(env::args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("{message}\n\n{USAGE}");
+        process::exit(2);
+    });
+
+    match command {
+        Command::Run(options) => {
+            let tasks = match options.example {
+                true => load_examples(&options.selection),
+                false => load(&options.selection).map(|puzzles| {
+                    puzzles
+                        .into_iter()
+                        .map(|puzzle| Task {
+                            puzzle,
+                            example: None,
+                            checked: false,
+                        })
+                        .collect()
+                }),
+            };
+            if let Err(message) = tasks.and_then(|tasks| run(&tasks, &options)) {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+        Command::Bench(options) => {
+            if let Err(message) =
+                load(&options.selection).and_then(|puzzles| bench::run(&puzzles, &options))
+            {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+        Command::Verify(options) => {
+            if let Err(message) =
+                load(&options.selection).and_then(|puzzles| verify::run(&puzzles, &options))
+            {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+        Command::Check(selection) => {
+            if let Err(message) = check(&selection) {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+        Command::Batch(options) => {
+            if let Err(message) = batch::run(&options) {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+        Command::NewDay(options) => {
+            if let Err(message) = scaffold::run(&options) {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+        Command::Generate(options) => {
+            if let Err(message) = generate(&options) {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+        Command::Visualize(options) => {
+            let puzzles = match options.example {
+                true => load_examples(&options.selection)
+                    .map(|tasks| tasks.into_iter().map(|task| task.puzzle).collect()),
+                false => load(&options.selection),
+            };
+            if let Err(message) = puzzles.and_then(|puzzles| animation::run(&puzzles, &options)) {
+                eprintln!("{message}");
+                process::exit(1);
+            }
+        }
+        Command::List => list(&registry()),
+        Command::Help => println!("{USAGE}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn examples(args: &str) -> Result<Vec<Task>, String> {
+        match Command::from_args(args.split_whitespace().map(String::from)) {
+            Ok(Command::Run(options)) => load_examples(&options.selection),
+            _ => panic!("Expected run command"),
+        }
+    }
+
+    #[test]
+    fn examples_overridden() {
+        let tasks = examples("run --example --day 9").unwrap();
+        assert!(tasks[0].checked);
+        assert_eq!(
+            "13",
+            tasks[0].puzzle.solution(Part::First).unwrap().to_string()
+        );
+
+        let tasks = examples("run --example --day 9 --param 9.first_knots=3").unwrap();
+        assert!(!tasks[0].checked);
+        assert_eq!(
+            "7",
+            tasks[0].puzzle.solution(Part::First).unwrap().to_string()
+        );
+
+        assert!(examples("run --example --day 9 --param 9.bogus=1").is_err());
+    }
 }
@@ -0,0 +1,204 @@
+use std::{error::Error, fmt};
+
+// the block letters are 4 pixels wide and 6 pixels high, separated by a single column
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+
+// the letters the puzzles are known to draw
+const FONT: [(char, [&str; GLYPH_HEIGHT]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// monochrome image drawn row by row, the lit pixels are shown as `#`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+impl Framebuffer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn light(&mut self, x: usize, y: usize) {
+        self.pixels[y * self.width + x] = true;
+    }
+
+    // the pixels outside of the image are dark
+    pub fn is_lit(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.pixels[y * self.width + x]
+    }
+
+    pub fn rows(&self) -> Vec<String> {
+        (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| if self.is_lit(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Framebuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.rows().join("\n"))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OcrError {
+    InvalidHeight(usize),
+    // the position of the letter counted from 1 and the rows of its pixels
+    UnknownGlyph(usize, Vec<String>),
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::InvalidHeight(height) => write!(
+                f,
+                "Not able to read letters {height} pixels high, expected {GLYPH_HEIGHT}"
+            ),
+            OcrError::UnknownGlyph(position, rows) => {
+                write!(
+                    f,
+                    "Unknown glyph of the letter {position}:\n{}",
+                    rows.join("\n")
+                )
+            }
+        }
+    }
+}
+
+impl Error for OcrError {}
+
+// reads the block letters drawn from the left edge of the image
+pub fn read_letters(framebuffer: &Framebuffer) -> Result<String, OcrError> {
+    if framebuffer.height() != GLYPH_HEIGHT {
+        return Err(OcrError::InvalidHeight(framebuffer.height()));
+    }
+
+    let letters = framebuffer.width().div_ceil(GLYPH_WIDTH + 1);
+    (0..letters)
+        .map(|idx| {
+            let left = idx * (GLYPH_WIDTH + 1);
+            let glyph: Vec<String> = framebuffer
+                .rows()
+                .iter()
+                .map(|row| format!("{:.<GLYPH_WIDTH$}", row.get(left..).unwrap_or("")))
+                .map(|row| String::from(&row[..GLYPH_WIDTH]))
+                .collect();
+            FONT.iter()
+                .find(|(_, rows)| *rows == glyph[..])
+                .map(|(letter, _)| *letter)
+                .ok_or(OcrError::UnknownGlyph(idx + 1, glyph))
+        })
+        .collect()
+}
+
+// the letters of the font the image can be drawn with
+pub fn known_letters() -> impl Iterator<Item = char> {
+    FONT.iter().map(|(letter, _)| *letter)
+}
+
+// draws the block letters the way they are read, missing when a letter is not in the font
+pub fn draw_letters(letters: &str) -> Option<Framebuffer> {
+    let width = (letters.chars().count() * (GLYPH_WIDTH + 1)).saturating_sub(1);
+    let mut framebuffer = Framebuffer::new(width, GLYPH_HEIGHT);
+    for (idx, letter) in letters.chars().enumerate() {
+        let (_, rows) = FONT.iter().find(|(known, _)| *known == letter)?;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, _) in row.char_indices().filter(|(_, pixel)| *pixel == '#') {
+                framebuffer.light(idx * (GLYPH_WIDTH + 1) + x, y);
+            }
+        }
+    }
+    Some(framebuffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framebuffer(picture: &str) -> Framebuffer {
+        let rows: Vec<&str> = picture.lines().collect();
+        let mut framebuffer = Framebuffer::new(rows[0].len(), rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, pixel) in row.chars().enumerate() {
+                if pixel == '#' {
+                    framebuffer.light(x, y);
+                }
+            }
+        }
+        framebuffer
+    }
+
+    #[test]
+    fn letters_read() {
+        let picture = "\
+####...##.#..#.###..#..#.#....###..####.
+#.......#.#..#.#..#.#..#.#....#..#....#.
+###.....#.#..#.###..#..#.#....#..#...#..
+#.......#.#..#.#..#.#..#.#....###...#...
+#....#..#.#..#.#..#.#..#.#....#.#..#....
+#.....##...##..###...##..####.#..#.####.";
+        let framebuffer = framebuffer(picture);
+        assert_eq!(picture, framebuffer.to_string());
+        assert_eq!(Ok(String::from("FJUBULRZ")), read_letters(&framebuffer));
+
+        let letters: String = known_letters().collect();
+        let drawn = draw_letters(&letters).unwrap();
+        assert_eq!(Ok(letters), read_letters(&drawn));
+        assert_eq!(None, draw_letters("FX"));
+    }
+
+    #[test]
+    fn unknown_glyph() {
+        let framebuffer =
+            framebuffer(".##..#..#\n#..#.#..#\n#..#.####\n####.#..#\n#..#.#..#\n#..#.#.#.");
+        assert_eq!(
+            Err(OcrError::UnknownGlyph(
+                2,
+                ["#..#", "#..#", "####", "#..#", "#..#", "#.#."]
+                    .map(String::from)
+                    .to_vec()
+            )),
+            read_letters(&framebuffer)
+        );
+        assert_eq!(
+            Err(OcrError::InvalidHeight(1)),
+            read_letters(&Framebuffer::new(4, 1))
+        );
+    }
+}
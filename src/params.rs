@@ -0,0 +1,180 @@
+use std::{fs, io, path::Path, str::FromStr};
+
+use crate::Solution;
+
+// the typed parameters of a puzzle, set by their names from the command line or the config
+pub trait Parameters {
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String>;
+    // the names of all the parameters with their current values
+    fn values(&self) -> Vec<(&'static str, String)>;
+}
+
+// declares the parameters of a puzzle together with their defaults
+macro_rules! parameters {
+    ($parameters:ident { $($name:ident: $ty:ty = $default:expr),* $(,)? }) => {
+        pub struct $parameters {
+            $(pub $name: $ty),*
+        }
+
+        impl Default for $parameters {
+            fn default() -> Self {
+                Self {
+                    $($name: $default),*
+                }
+            }
+        }
+
+        impl crate::Parameters for $parameters {
+            fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+                match name {
+                    $(stringify!($name) => {
+                        self.$name = value.parse().map_err(|_| {
+                            format!("Invalid value `{value}` of the parameter `{name}`")
+                        })?
+                    })*
+                    _ => {
+                        let names = [$(stringify!($name)),*];
+                        return Err(format!(
+                            "Unknown parameter `{name}`, expected one of {}",
+                            names.join(", ")
+                        ));
+                    }
+                }
+                Ok(())
+            }
+
+            fn values(&self) -> Vec<(&'static str, String)> {
+                vec![$((stringify!($name), self.$name.to_string())),*]
+            }
+        }
+    };
+}
+
+// a parameter of the puzzle of the given day, written as `day.name=value`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Override {
+    pub day: u8,
+    pub name: String,
+    pub value: String,
+}
+
+impl FromStr for Override {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid parameter `{text}`, expected DAY.NAME=VALUE");
+        let (key, value) = text.split_once('=').ok_or_else(invalid)?;
+        let (day, name) = key.trim().split_once('.').ok_or_else(invalid)?;
+        Ok(Self {
+            day: day.parse().map_err(|_| invalid())?,
+            name: String::from(name),
+            value: String::from(value.trim()),
+        })
+    }
+}
+
+// every line of the config holds a single parameter, the lines starting with `#` are comments
+pub fn read_config(path: &Path) -> Result<Vec<Override>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(format!("Not able to read {}: {error}", path.display())),
+    };
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(idx, line)| {
+            line.parse()
+                .map_err(|error| format!("{error} in {} on line {}", path.display(), idx + 1))
+        })
+        .collect()
+}
+
+pub fn set_parameter(puzzle: &mut dyn Solution, name: &str, value: &str) -> Result<(), String> {
+    let day = puzzle.puzzle_info().day();
+    match puzzle.parameters() {
+        Some(parameters) => parameters
+            .set(name, value)
+            .map_err(|error| format!("Day {day}: {error}")),
+        None => Err(format!("Day {day}: The puzzle has no parameters")),
+    }
+}
+
+// applies the parameters of the day of the puzzle in their order, so the later ones win
+pub fn configure(puzzle: &mut dyn Solution, overrides: &[Override]) -> Result<(), String> {
+    let day = puzzle.puzzle_info().day();
+    for parameter in overrides.iter().filter(|parameter| parameter.day == day) {
+        set_parameter(puzzle, &parameter.name, &parameter.value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{registry, Answer, Part};
+
+    use super::*;
+
+    #[test]
+    fn parameters_of_the_day() {
+        let overrides: Vec<Override> = ["15.row=10", "7.size_limit = 100", "15.row=11"]
+            .iter()
+            .map(|text| text.parse().unwrap())
+            .collect();
+        assert_eq!(
+            Override {
+                day: 7,
+                name: String::from("size_limit"),
+                value: String::from("100"),
+            },
+            overrides[1]
+        );
+        assert!("15row=10".parse::<Override>().is_err());
+        assert!("x.row=10".parse::<Override>().is_err());
+
+        let registration = &registry()[14];
+        let mut puzzle =
+            registration.from_input("Sensor at x=8, y=7: closest beacon is at x=2, y=10");
+        configure(puzzle.as_mut(), &overrides).unwrap();
+        let values = puzzle.parameters().unwrap().values();
+        assert_eq!(("row", String::from("11")), values[0]);
+        assert_eq!(Ok(Answer::Integer(11)), puzzle.solution(Part::First));
+
+        let invalid = ["15.rows=1".parse().unwrap()];
+        assert!(configure(puzzle.as_mut(), &invalid).is_err());
+        let invalid = ["15.row=x".parse().unwrap()];
+        assert!(configure(puzzle.as_mut(), &invalid).is_err());
+        // the tuning frequency is divided by the multiplier
+        let invalid = ["15.multiplier=0".parse().unwrap()];
+        assert!(configure(puzzle.as_mut(), &invalid).is_err());
+        let mut puzzle = registry()[0].from_input("1");
+        let invalid = ["1.elves=3".parse().unwrap()];
+        assert!(configure(puzzle.as_mut(), &invalid).is_err());
+    }
+
+    #[test]
+    fn config_file() {
+        let path = std::env::temp_dir().join("code_advent_2022.conf");
+        fs::write(
+            &path,
+            "# the example\n15.row = 10\n\n15.bound=20\n11 rounds\n",
+        )
+        .unwrap();
+        let error = read_config(&path).unwrap_err();
+        assert!(error.ends_with("on line 5"));
+
+        fs::write(&path, "# the example\n15.row = 10\n\n15.bound=20\n").unwrap();
+        let overrides = read_config(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            vec![
+                "15.row=10".parse::<Override>().unwrap(),
+                "15.bound=20".parse().unwrap()
+            ],
+            overrides
+        );
+        assert_eq!(Ok(Vec::new()), read_config(&path));
+    }
+}
@@ -0,0 +1,100 @@
+use std::{error::Error, fmt};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    pub day: u8,
+    // both the line and the column are counted from 1
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Day {}, line {}, column {}: expected {}, found {}",
+            self.day, self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+// single line of the puzzle input stripped of the surrounding whitespace,
+// remembering where it came from so that the errors can point to the original text
+pub struct InputLine<'a> {
+    pub text: &'a str,
+    day: u8,
+    idx: usize,
+    indent: usize,
+}
+
+impl<'a> InputLine<'a> {
+    pub fn new(day: u8, idx: usize, line: &'a str) -> Self {
+        let text = line.trim_start();
+        Self {
+            indent: line.len() - text.len(),
+            text: text.trim_end(),
+            day,
+            idx,
+        }
+    }
+
+    // builds the error for the position given as byte offset within the trimmed text
+    pub fn error(&self, offset: usize, expected: &str) -> ParseError {
+        let found = match self.text.get(offset..) {
+            Some(rest) if !rest.is_empty() => {
+                let token = rest.split_whitespace().next().unwrap_or(rest);
+                format!("`{token}`")
+            }
+            _ => String::from("end of line"),
+        };
+
+        ParseError {
+            day: self.day,
+            line: self.idx + 1,
+            column: self.indent
+                + self
+                    .text
+                    .char_indices()
+                    .take_while(|(idx, _)| *idx < offset)
+                    .count()
+                + 1,
+            expected: String::from(expected),
+            found,
+        }
+    }
+
+    // parses the token starting at the given offset
+    pub fn parse<T: std::str::FromStr>(
+        &self,
+        offset: usize,
+        token: &str,
+        expected: &str,
+    ) -> Result<T, ParseError> {
+        token.parse().map_err(|_| self.error(offset, expected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_position() {
+        let line = InputLine::new(4, 2, "    2-4,x-8  ");
+        assert_eq!(
+            ParseError {
+                day: 4,
+                line: 3,
+                column: 9,
+                expected: String::from("a section id"),
+                found: String::from("`x-8`"),
+            },
+            line.error(4, "a section id")
+        );
+        assert_eq!("end of line", line.error(7, "`,`").found);
+    }
+}
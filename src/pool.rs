@@ -0,0 +1,71 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+// the workers get a stack as large as the main thread has on linux, the puzzles solved on
+// them recurse as deep as on the main thread
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+// applies the function to every item on the given number of worker threads,
+// the results keep the order of the items, a single worker is the calling thread itself
+pub fn map<'a, T: Sync, R: Send, F: Fn(&'a T) -> R + Sync>(
+    items: &'a [T],
+    jobs: usize,
+    f: F,
+) -> Vec<R> {
+    let workers = jobs.clamp(1, items.len().max(1));
+    if workers == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next_idx = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let (sender, next_idx, f) = (sender.clone(), &next_idx, &f);
+            thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn_scoped(scope, move || loop {
+                    let idx = next_idx.fetch_add(1, Ordering::Relaxed);
+                    match items.get(idx) {
+                        Some(item) => sender.send((idx, f(item))).unwrap(),
+                        None => break,
+                    }
+                })
+                .expect("failed to spawn a worker thread");
+        }
+    });
+    drop(sender);
+
+    let mut results: Vec<(usize, R)> = receiver.into_iter().collect();
+    results.sort_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_in_order_of_items() {
+        let items: Vec<u64> = (1..=20).collect();
+        let squares = map(&items, 4, |n| {
+            // the later items finish first
+            thread::sleep(std::time::Duration::from_millis(20 - n));
+            n * n
+        });
+        assert_eq!(items.iter().map(|n| n * n).collect::<Vec<_>>(), squares);
+        assert!(map(&[] as &[u64], 4, |n| *n).is_empty());
+
+        // a single worker runs on the calling thread
+        let caller = thread::current().id();
+        assert_eq!(vec![caller], map(&[1], 1, |_| thread::current().id()));
+        let workers = map(&[1, 2], 2, |_| thread::current().id());
+        assert!(workers.iter().all(|worker| *worker != caller));
+    }
+}
@@ -0,0 +1,189 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{Generated, Part, Registration, Rng, Solution};
+
+// the property holds when the answers of the puzzle agree with the reference implementation,
+// the error describes the disagreement
+pub type Property<'a> = dyn Fn(&Generated, &dyn Solution) -> Result<(), String> + 'a;
+
+#[derive(Debug)]
+pub struct Counterexample {
+    pub seed: u64,
+    pub generated: Generated,
+    pub error: String,
+}
+
+// checks the property with the inputs generated from the seeds `0..cases`, a failure is
+// reported with the smallest input still failing
+pub fn check(registration: &Registration, cases: u64, size: usize, property: &Property) {
+    if let Some(counterexample) = counterexample(registration, cases, size, property) {
+        let params: Vec<String> = counterexample
+            .generated
+            .params
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        panic!(
+            "{}: the property failed for the seed {}, size {size}, shrunk to the input\n{}\n\
+             with the parameters [{}]: {}",
+            registration.name,
+            counterexample.seed,
+            counterexample.generated.input,
+            params.join(", "),
+            counterexample.error
+        );
+    }
+}
+
+pub fn counterexample(
+    registration: &Registration,
+    cases: u64,
+    size: usize,
+    property: &Property,
+) -> Option<Counterexample> {
+    for seed in 0..cases {
+        let generated = (registration.generate)(&mut Rng::new(seed), size);
+        // the generated inputs must be valid, otherwise the generator is broken
+        let Err(error) = holds(registration, &generated, property).unwrap_or_else(|error| {
+            panic!(
+                "{}: invalid input generated from the seed {seed}: {error}",
+                registration.name
+            )
+        }) else {
+            continue;
+        };
+        let (generated, error) = shrink(registration, generated, error, property);
+        return Some(Counterexample {
+            seed,
+            generated,
+            error,
+        });
+    }
+    None
+}
+
+// solves the part of the puzzle expecting the answer of the reference implementation
+pub fn compare(puzzle: &dyn Solution, part: Part, expected: impl ToString) -> Result<(), String> {
+    let expected = expected.to_string();
+    match puzzle.solution(part) {
+        Ok(answer) if answer.to_string() == expected => Ok(()),
+        Ok(answer) => Err(format!(
+            "the {part} part expected {expected}, found {answer}"
+        )),
+        Err(error) => Err(format!("the {part} part failed: {error}")),
+    }
+}
+
+// the outer error tells the input is not valid, the inner one that the property failed,
+// a panic of the puzzle counts as a failure as well
+fn holds(
+    registration: &Registration,
+    generated: &Generated,
+    property: &Property,
+) -> Result<Result<(), String>, String> {
+    let puzzle = registration.build_generated(registration.name, generated);
+    puzzle.parse().map_err(|error| error.to_string())?;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| property(generated, puzzle.as_ref())));
+    Ok(result.unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(format!("the puzzle panicked: {message}"))
+    }))
+}
+
+// keeps taking the first smaller input which is still valid and still fails
+fn shrink(
+    registration: &Registration,
+    mut generated: Generated,
+    mut error: String,
+    property: &Property,
+) -> (Generated, String) {
+    'shrinking: loop {
+        for input in smaller_inputs(&generated.input) {
+            let candidate = Generated {
+                input,
+                params: generated.params.clone(),
+            };
+            if let Ok(Err(candidate_error)) = holds(registration, &candidate, property) {
+                (generated, error) = (candidate, candidate_error);
+                continue 'shrinking;
+            }
+        }
+        return (generated, error);
+    }
+}
+
+// the input without a block of lines, from the halves down to the single lines, and for the
+// grids the input without one of the columns
+fn smaller_inputs(input: &str) -> Vec<String> {
+    let lines: Vec<&str> = input.lines().collect();
+    let mut inputs = Vec::new();
+
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        for start in (0..lines.len()).step_by(chunk) {
+            let rest: Vec<&str> = lines[..start]
+                .iter()
+                .chain(lines[(start + chunk).min(lines.len())..].iter())
+                .copied()
+                .collect();
+            inputs.push(rest.join("\n"));
+        }
+        chunk /= 2;
+    }
+    if lines.len() == 1 && !lines[0].is_empty() {
+        inputs.push(String::new());
+    }
+
+    let width = lines.first().map_or(0, |line| line.len());
+    let is_grid = width > 1
+        && lines
+            .iter()
+            .all(|line| line.len() == width && line.is_ascii());
+    if is_grid {
+        for col in 0..width {
+            let rest: Vec<String> = lines
+                .iter()
+                .map(|line| format!("{}{}", &line[..col], &line[col + 1..]))
+                .collect();
+            inputs.push(rest.join("\n"));
+        }
+    }
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{registry, Answer};
+
+    use super::*;
+
+    #[test]
+    fn counterexample_shrunk() {
+        // the most calories never reach the limit, which fails as soon as a single elf does
+        let registration = &registry()[0];
+        let property = |_: &Generated, puzzle: &dyn Solution| match puzzle.solution(Part::First) {
+            Ok(Answer::Integer(calories)) if calories >= 100000 => {
+                Err(format!("{calories} calories"))
+            }
+            _ => Ok(()),
+        };
+        let found = counterexample(registration, 20, 20, &property).unwrap();
+        let lines: Vec<&str> = found.generated.input.lines().collect();
+        assert!(lines.iter().all(|line| !line.is_empty()));
+        assert!(lines.len() <= 8, "{lines:?}");
+        assert!(found.error.ends_with("calories"));
+
+        assert!(counterexample(registration, 5, 20, &|_, _| Ok(())).is_none());
+    }
+
+    #[test]
+    fn smaller_grids() {
+        let inputs = smaller_inputs("123\n456");
+        assert_eq!(vec!["456", "123", "23\n56", "13\n46", "12\n45"], inputs);
+        assert_eq!(vec![String::new()], smaller_inputs("1"));
+    }
+}
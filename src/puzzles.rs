@@ -1,30 +1,188 @@
-#[path = "puzzles/1.rs"]
-pub mod first_puzzle;
-#[path = "puzzles/2.rs"]
-pub mod second_puzzle;
-#[path = "puzzles/3.rs"]
-pub mod third_puzzle;
-#[path = "puzzles/4.rs"]
-pub mod fourth_puzzle;
-#[path ="puzzles/5.rs"]
-pub mod fifth_puzzle;
-#[path ="puzzles/6.rs"]
-pub mod sixth_puzzle;
-#[path ="puzzles/7.rs"]
-pub mod seventh_puzzle;
-#[path ="puzzles/8.rs"]
-pub mod eighth_puzzle;
-#[path ="puzzles/9.rs"]
-pub mod ninth_puzzle;
-#[path ="puzzles/10.rs"]
-pub mod tenth_puzzle;
-#[path ="puzzles/11.rs"]
-pub mod eleventh_puzzle;
-#[path ="puzzles/12.rs"]
-pub mod twelfth_puzzle;
-#[path ="puzzles/13.rs"]
-pub mod thirteenth_puzzle;
-#[path ="puzzles/14.rs"]
-pub mod fourteenth_puzzle;
-#[path ="puzzles/15.rs"]
-pub mod fifteenth_puzzle;
+use crate::Registration;
+
+// declares the registration of a puzzle, every puzzle module registers itself with it,
+// the tests solving the examples of the puzzle are generated here as well
+macro_rules! register {
+    ($day:literal, $name:literal, $input:literal, $puzzle:ident, $examples:expr, $generate:expr) => {
+        pub const REGISTRATION: crate::Registration = crate::Registration {
+            day: $day,
+            name: $name,
+            input: $input,
+            examples: $examples,
+            generate: $generate,
+            build: |puzzle| Box::new(crate::Parsed::new($puzzle::new(puzzle))),
+        };
+
+        #[cfg(test)]
+        mod example_tests {
+            #[test]
+            fn examples() {
+                crate::puzzles::check_examples(&super::REGISTRATION);
+            }
+        }
+
+        impl $puzzle {
+            pub fn from_input(input: &str) -> Self {
+                Self::new(crate::PuzzleInfo::new($day, $name, input))
+            }
+        }
+    };
+}
+
+// declares the puzzle modules and collects their registrations, adding a day means listing
+// its module here once
+macro_rules! puzzles {
+    ($(#[path = $path:literal] pub mod $module:ident;)*) => {
+        $(
+            #[path = $path]
+            pub mod $module;
+        )*
+
+        pub fn registry() -> Vec<Registration> {
+            let mut registry = vec![$($module::REGISTRATION),*];
+            registry.sort_by_key(|registration| registration.day);
+            registry
+        }
+    };
+}
+
+puzzles! {
+    #[path = "puzzles/1.rs"]
+    pub mod first_puzzle;
+    #[path = "puzzles/2.rs"]
+    pub mod second_puzzle;
+    #[path = "puzzles/3.rs"]
+    pub mod third_puzzle;
+    #[path = "puzzles/4.rs"]
+    pub mod fourth_puzzle;
+    #[path = "puzzles/5.rs"]
+    pub mod fifth_puzzle;
+    #[path = "puzzles/6.rs"]
+    pub mod sixth_puzzle;
+    #[path = "puzzles/7.rs"]
+    pub mod seventh_puzzle;
+    #[path = "puzzles/8.rs"]
+    pub mod eighth_puzzle;
+    #[path = "puzzles/9.rs"]
+    pub mod ninth_puzzle;
+    #[path = "puzzles/10.rs"]
+    pub mod tenth_puzzle;
+    #[path = "puzzles/11.rs"]
+    pub mod eleventh_puzzle;
+    #[path = "puzzles/12.rs"]
+    pub mod twelfth_puzzle;
+    #[path = "puzzles/13.rs"]
+    pub mod thirteenth_puzzle;
+    #[path = "puzzles/14.rs"]
+    pub mod fourteenth_puzzle;
+    #[path = "puzzles/15.rs"]
+    pub mod fifteenth_puzzle;
+}
+
+#[cfg(test)]
+fn check_examples(registration: &Registration) {
+    use crate::Part;
+
+    assert!(!registration.examples.is_empty());
+    for (puzzle, example) in registration.examples() {
+        puzzle.parse().unwrap();
+        for part in Part::ALL {
+            if let Some(expected) = example.answer(part) {
+                let answer = puzzle.solution(part).unwrap();
+                let name = puzzle.puzzle_info().name();
+                assert_eq!(expected, answer.to_string(), "{name}, {part} part");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{
+        parse::ParseError, Answer, Parsed, Part, Puzzle, PuzzleInfo, Solution, SolveError,
+    };
+
+    use super::*;
+
+    // counts how many times the input was parsed
+    struct CountingPuzzle {
+        puzzle: PuzzleInfo,
+        parsed: AtomicUsize,
+    }
+
+    impl Puzzle for CountingPuzzle {
+        type Model = usize;
+
+        fn puzzle_info(&self) -> &PuzzleInfo {
+            &self.puzzle
+        }
+
+        fn parse(&self) -> Result<usize, ParseError> {
+            self.parsed.fetch_add(1, Ordering::SeqCst);
+            Ok(self.puzzle.lines().count())
+        }
+
+        fn summary(&self, lines: &usize) -> String {
+            format!("{lines} lines")
+        }
+
+        fn solve(&self, lines: &usize, part: Part) -> Result<Answer, SolveError> {
+            Ok((lines * part.number() as usize).into())
+        }
+    }
+
+    #[test]
+    fn registry_sorted_by_unique_days() {
+        let days: Vec<u8> = registry()
+            .iter()
+            .map(|registration| registration.day)
+            .collect();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Some(&1), days.first());
+    }
+
+    #[test]
+    fn generated_inputs() {
+        for registration in registry() {
+            for (seed, size) in [(0, 1), (1, 10), (2, 40)] {
+                let puzzle = registration.generated(seed, size);
+                let name = puzzle.puzzle_info().name();
+                assert!(puzzle.parse().is_ok(), "{name}");
+                for part in Part::ALL {
+                    assert!(puzzle.solution(part).is_ok(), "{name}, {part} part");
+                }
+                let again = registration.generated(seed, size);
+                assert_eq!(puzzle.puzzle_info().input(), again.puzzle_info().input());
+            }
+        }
+    }
+
+    #[test]
+    fn puzzle_from_input() {
+        let puzzle = first_puzzle::FirstPuzzle::from_input("1000\n2000\n\n4000");
+        assert_eq!(1, puzzle.puzzle_info().day());
+        let calories = puzzle.parse().unwrap();
+        assert_eq!(
+            Ok(Answer::Integer(4000)),
+            puzzle.solve(&calories, Part::First)
+        );
+    }
+
+    #[test]
+    fn parsed_once() {
+        let puzzle = Parsed::new(CountingPuzzle {
+            puzzle: PuzzleInfo::new(1, "Test", "a\nb"),
+            parsed: AtomicUsize::new(0),
+        });
+        assert_eq!(Ok(String::from("2 lines")), puzzle.parse());
+        assert_eq!(Ok(Answer::Integer(2)), puzzle.solution(Part::First));
+        assert_eq!(Ok(Answer::Integer(4)), puzzle.solution(Part::Second));
+        assert_eq!(1, puzzle.puzzle.parsed.load(Ordering::SeqCst));
+
+        // the benchmark parses the input again without replacing the model
+        assert_eq!(Ok(()), puzzle.reparse());
+        assert_eq!(2, puzzle.puzzle.parsed.load(Ordering::SeqCst));
+    }
+}
@@ -1,57 +1,98 @@
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, Answer, Example, Generated, Part, Puzzle, PuzzleInfo, Rng, SolveError,
+};
 
 // for more details check the https://adventofcode.com/2022/day/1
 pub struct FirstPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for FirstPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.most_calories(),
-            self.sum_top_three_calories(),
-        );
+register!(
+    1,
+    "First Puzzle - Calorie Counting",
+    "1.txt",
+    FirstPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000",
+    answers: [Some("24000"), Some("45000")],
+    params: &[],
+}];
+
+impl Puzzle for FirstPuzzle {
+    // the calories carried by every elf
+    type Model = Vec<i64>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Vec<i64>, ParseError> {
+        self.get_calories_per_elf()
+    }
+
+    fn summary(&self, calories_per_elf: &Vec<i64>) -> String {
+        format!("{} elves", calories_per_elf.len())
+    }
+
+    fn solve(&self, calories_per_elf: &Vec<i64>, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(Self::most_calories(calories_per_elf).into()),
+            Part::Second => Ok(Self::sum_top_three_calories(calories_per_elf).into()),
+        }
     }
 }
 
 impl FirstPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new("First Puzzle - Calorie Counting", "./inputs/1.txt"),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
-    fn most_calories(&self) -> i32 {
-        *self
-            .get_calories_per_elf()
+    fn most_calories(calories_per_elf: &[i64]) -> i64 {
+        *calories_per_elf
             .iter()
             .max()
             .expect("the vector with calories is empty")
     }
 
-    fn sum_top_three_calories(&self) -> i32 {
-        let mut calories_per_elf = self.get_calories_per_elf();
+    fn sum_top_three_calories(calories_per_elf: &[i64]) -> i64 {
+        let mut calories_per_elf = calories_per_elf.to_vec();
         calories_per_elf.sort();
-        *&calories_per_elf[calories_per_elf.len() - 3..].iter().sum()
+        calories_per_elf.iter().rev().take(3).sum()
     }
 
-    fn get_calories_per_elf(&self) -> Vec<i32> {
+    fn get_calories_per_elf(&self) -> Result<Vec<i64>, ParseError> {
         let mut calories_per_elv = Vec::new();
         let mut acc_calories_per_elf = 0;
-        let mut lines = self.puzzle.input.lines();
+        let mut lines = self.puzzle.lines();
 
         loop {
             match lines.next() {
                 Some(line) => {
-                    if line.trim().is_empty() {
+                    if line.text.is_empty() {
                         calories_per_elv.push(acc_calories_per_elf);
                         acc_calories_per_elf = 0;
                         continue;
                     }
-                    acc_calories_per_elf += line
-                        .trim()
-                        .parse::<i32>()
-                        .expect("Failed to parse the line from input into integer");
+                    // a single item fits into i32, the sums of them are kept wider so they
+                    // cannot overflow
+                    acc_calories_per_elf +=
+                        i64::from(line.parse::<i32>(0, line.text, "a number of calories")?);
                 }
                 None => {
                     calories_per_elv.push(acc_calories_per_elf);
@@ -59,56 +100,51 @@ impl FirstPuzzle {
                 }
             }
         }
-        calories_per_elv
+        Ok(calories_per_elv)
     }
 }
 
+// calorie lists of the elves separated by blank lines
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let elves: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            let items = rng.range(1..=8);
+            let calories: Vec<String> = (0..items)
+                .map(|_| rng.range(1000..=60000).to_string())
+                .collect();
+            calories.join("\n")
+        })
+        .collect();
+    elves.join("\n\n").into()
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::PuzzleInfo;
+
     use super::*;
+
     #[test]
-    fn most_calories() {
-        // the most calories carried by Elf should be 24000
-        assert_eq!(
-            24000,
-            FirstPuzzle {
-                puzzle: get_puzzle_info()
-            }
-            .most_calories()
-        );
+    fn invalid_calories() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("5000", "5OOO");
+        let error = FirstPuzzle { puzzle }.get_calories_per_elf().unwrap_err();
+        assert_eq!((7, 1), (error.line, error.column));
+        assert_eq!("`5OOO`", error.found);
     }
 
     #[test]
-    fn sum_top_three_calories() {
-        // The sum of the Calories carried by these three elves is 45000
+    fn calories_beyond_i32() {
+        let puzzle = FirstPuzzle::new(PuzzleInfo::new(1, "Test", "2000000000\n2000000000\n\n1"));
+        let calories_per_elf = puzzle.get_calories_per_elf().unwrap();
+        assert_eq!(vec![4000000000, 1], calories_per_elf);
         assert_eq!(
-            45000,
-            FirstPuzzle {
-                puzzle: get_puzzle_info()
-            }
-            .sum_top_three_calories()
+            4000000001,
+            FirstPuzzle::sum_top_three_calories(&calories_per_elf)
         );
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "1000
-                2000
-                3000
-                
-                4000
-                
-                5000
-                6000
-                
-                7000
-                8000
-                9000
-                
-                10000",
-            ),
-        }
+        PuzzleInfo::new(1, "Test", EXAMPLES[0].input)
     }
 }
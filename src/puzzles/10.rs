@@ -1,62 +1,291 @@
+use std::num::NonZeroUsize;
+
 use regex::Regex;
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    ocr::{draw_letters, known_letters, read_letters, Framebuffer},
+    parse::ParseError,
+    visualize::{Pixel, LIT},
+    Answer, Example, Frame, Generated, Parameters, Part, Point2, Puzzle, PuzzleInfo, Rng,
+    SolveError, Visualize,
+};
 
 pub struct TenthPuzzle {
     puzzle: PuzzleInfo,
+    params: TenthParameters,
 }
 
-impl Solution for TenthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.get_handheld().sum_of_signal_strenghts().to_string(),
-            String::from("FJUBULRZ"),
-        );
+parameters!(TenthParameters {
+    // the signal strength is captured during every interval-th cycle from the first to the last
+    first_cycle: i32 = 20,
+    cycle_interval: NonZeroUsize = NonZeroUsize::new(40).unwrap(),
+    last_cycle: i32 = 220,
+});
+
+register!(
+    10,
+    "Tenth Puzzle - Cathode-Ray Tube",
+    "10.txt",
+    TenthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop",
+    // the example draws a test pattern instead of the letters
+    answers: [Some("13140"), None],
+    params: &[],
+}];
+
+impl Puzzle for TenthPuzzle {
+    type Model = Hanheld;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Hanheld, ParseError> {
+        self.get_handheld()
+    }
+
+    fn summary(&self, handheld: &Hanheld) -> String {
+        format!("{} instructions", handheld.instructions.len())
+    }
+
+    fn solve(&self, handheld: &Hanheld, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => {
+                let cycles_to_capture = (self.params.first_cycle..=self.params.last_cycle)
+                    .step_by(self.params.cycle_interval.get())
+                    .collect();
+                Ok(handheld.sum_of_signal_strenghts(cycles_to_capture).into())
+            }
+            Part::Second => Ok(read_letters(&handheld.render())?.into()),
+        }
+    }
+
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        Some(&mut self.params)
+    }
 
-        self.get_handheld().render();
+    fn visualization(&self) -> Option<&dyn Visualize<Model = Hanheld>> {
+        Some(self)
     }
 }
 
-struct Hanheld {
-    cpu: CPU,
-    crt: CRT,
+const SPRITE: Pixel = Pixel::new('=', [60, 90, 160]);
+const BEAM: Pixel = Pixel::new('@', [250, 80, 80]);
+
+impl Visualize for TenthPuzzle {
+    type Model = Hanheld;
+
+    // the display after every cycle with the sprite and the beam drawing the pixel, the signal
+    // strength of the first part is taken during the same cycles
+    fn frames(&self, handheld: &Hanheld, _part: Part, sink: &mut dyn FnMut(&Frame)) {
+        handheld
+            .crt
+            .execute_instructions(&handheld.instructions, &mut |framebuffer, cycle, x| {
+                let (col_idx, row_idx) = (cycle % framebuffer.width(), cycle / framebuffer.width());
+                let mut frame = Frame::from(framebuffer);
+                for sprite_idx in x - 1..=x + 1 {
+                    if (0..framebuffer.width() as i32).contains(&sprite_idx)
+                        && !framebuffer.is_lit(sprite_idx as usize, row_idx)
+                    {
+                        frame.set(Point2::new(sprite_idx.into(), row_idx as i64), SPRITE);
+                    }
+                }
+                let pixel = match framebuffer.is_lit(col_idx, row_idx) {
+                    true => Pixel::new(LIT.glyph, BEAM.color),
+                    false => BEAM,
+                };
+                frame.set(Point2::new(col_idx as i64, row_idx as i64), pixel);
+                sink(&frame);
+            });
+    }
+}
+
+pub struct Hanheld {
+    crt: Crt,
     instructions: Vec<Instruction>,
 }
 
 impl Hanheld {
     fn new(instructions: Vec<Instruction>) -> Self {
         Self {
-            cpu: CPU::new(),
-            crt: CRT::new(),
+            crt: Crt::new(),
             instructions,
         }
     }
 
-    fn sum_of_signal_strenghts(&self) -> i32 {
-        self.cpu
+    fn sum_of_signal_strenghts(&self, cycles_to_capture: Vec<i32>) -> i32 {
+        Cpu::new(cycles_to_capture)
             .execute_instructions(&self.instructions)
             .iter()
             .sum()
     }
 
-    fn render(&self) {
-        self.crt.execute_instructions(&self.instructions);
+    fn render(&self) -> Framebuffer {
+        self.crt
+            .execute_instructions(&self.instructions, &mut |_, _, _| {})
     }
 }
 
-struct CPU {
+struct Cpu {
     cycles_to_capture: Vec<i32>,
 }
 
-impl CPU {
-    fn new() -> Self {
-        Self {
-            cycles_to_capture: vec![20, 60, 100, 140, 180, 220],
-        }
+impl Cpu {
+    fn new(cycles_to_capture: Vec<i32>) -> Self {
+        Self { cycles_to_capture }
     }
 
-    fn execute_instructions(&self, instructions: &Vec<Instruction>) -> Vec<i32> {
+    fn execute_instructions(&self, instructions: &[Instruction]) -> Vec<i32> {
         let mut captured_signal_strengths = Vec::new();
         let mut x = 1;
         let mut total_iterations: i32 = 0;
@@ -68,9 +297,8 @@ impl CPU {
                     captured_signal_strengths.push(x * total_iterations);
                 }
             }
-            match instruction {
-                Instruction::AddX(n) => x += n,
-                _ => {}
+            if let Instruction::AddX(n) = instruction {
+                x += n
             }
         });
 
@@ -78,13 +306,13 @@ impl CPU {
     }
 }
 
-struct CRT {
-    display_height: i32,
-    display_width: i32,
+struct Crt {
+    display_height: usize,
+    display_width: usize,
     sprite_length: i32,
 }
 
-impl CRT {
+impl Crt {
     fn new() -> Self {
         Self {
             display_height: 6,
@@ -93,39 +321,35 @@ impl CRT {
         }
     }
 
-    fn execute_instructions(&self, instructions: &Vec<Instruction>) {
-        let mut col_idx: i32 = 0;
-        let mut row_idx = 0;
-        let mut row = String::new();
-
+    // the pixels are drawn one per cycle, row by row, and the ones beyond the display are lost,
+    // the observer sees the display after every pixel with the cycle and the sprite position
+    fn execute_instructions(
+        &self,
+        instructions: &[Instruction],
+        observe: &mut dyn FnMut(&Framebuffer, usize, i32),
+    ) -> Framebuffer {
+        let mut framebuffer = Framebuffer::new(self.display_width, self.display_height);
+        let mut cycle = 0;
         let mut x = 1;
 
         instructions.iter().for_each(|instruction| {
             for _ in 0..instruction.get_cycle_length() {
-                if col_idx == self.display_width {
-                    col_idx = 0;
-                    row_idx += 1;
-                    println!("{row}");
-                    row = String::new();
-                }
-
-                if (x - col_idx).abs() < self.sprite_length {
-                    row.push('#')
-                } else {
-                    row.push('.')
+                let (col_idx, row_idx) = (cycle % self.display_width, cycle / self.display_width);
+                if row_idx < self.display_height && (x - col_idx as i32).abs() < self.sprite_length
+                {
+                    framebuffer.light(col_idx, row_idx);
                 }
-
-                col_idx += 1;
-
-                if row_idx == self.display_height - 1 && col_idx == self.display_width {
-                    println!("{row}");
+                if row_idx < self.display_height {
+                    observe(&framebuffer, cycle, x);
                 }
+                cycle += 1;
             }
-            match instruction {
-                Instruction::AddX(n) => x += n,
-                _ => {}
+            if let Instruction::AddX(n) = instruction {
+                x += n
             }
         });
+
+        framebuffer
     }
 }
 
@@ -144,29 +368,100 @@ impl Instruction {
 }
 
 impl TenthPuzzle {
-    pub fn new() -> Self {
+    pub fn new(puzzle: PuzzleInfo) -> Self {
         Self {
-            puzzle: PuzzleInfo::new("Tenth Puzzle - Cathode-Ray Tube", "./inputs/10.txt"),
+            puzzle,
+            params: TenthParameters::default(),
         }
     }
 
-    fn get_handheld(&self) -> Hanheld {
+    fn get_handheld(&self) -> Result<Hanheld, ParseError> {
         let mut instructions = Vec::new();
-        let re_addx = Regex::new(r".* (-?\d+)").unwrap();
+        let re_addx = Regex::new(r"^addx (-?\d+)$").unwrap();
+
+        for line in self.puzzle.lines().filter(|line| !line.text.is_empty()) {
+            if let Some(r_capture) = re_addx.captures(line.text) {
+                instructions.push(Instruction::AddX(line.parse(
+                    5,
+                    &r_capture[1],
+                    "a value",
+                )?))
+            } else if line.text == "noop" {
+                instructions.push(Instruction::Noop)
+            } else {
+                return Err(line.error(0, "`addx V` or `noop`"));
+            }
+        }
 
-        for line in self.puzzle.input.lines() {
-            let line = line.trim();
+        Ok(Hanheld::new(instructions))
+    }
+}
 
-            if re_addx.is_match(line) {
-                let r_capture = re_addx.captures(line).unwrap();
-                instructions.push(Instruction::AddX(*&r_capture[1].parse().unwrap()))
+// program drawing random letters on the display, followed by the instructions executed
+// once the display is complete
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let crt = Crt::new();
+    let letters: Vec<char> = known_letters().collect();
+    let cycles = crt.display_width * crt.display_height;
+    let sprite_positions: Vec<i32> = (-1..=crt.display_width as i32).collect();
+
+    let mut lines = loop {
+        let text: String = (0..crt.display_width / 5)
+            .map(|_| *rng.choose(&letters))
+            .collect();
+        let image = draw_letters(&text).unwrap();
+        // whether the sprite at the position draws the pixel of the cycle as it should be
+        let draws = |cycle: usize, x: i32| {
+            let (col_idx, row_idx) = (cycle % crt.display_width, cycle / crt.display_width);
+            cycle >= cycles
+                || ((x - col_idx as i32).abs() < crt.sprite_length)
+                    == image.is_lit(col_idx, row_idx)
+        };
+        // finishing[cycle][idx] tells whether the display can be completed from the cycle
+        // with the sprite at the position of the idx
+        let mut finishing = vec![vec![true; sprite_positions.len()]; cycles + 2];
+        for cycle in (0..cycles).rev() {
+            let can_add = sprite_positions
+                .iter()
+                .enumerate()
+                .any(|(idx, _)| finishing[cycle + 2][idx]);
+            for (idx, x) in sprite_positions.iter().enumerate() {
+                finishing[cycle][idx] = draws(cycle, *x)
+                    && (finishing[cycle + 1][idx] || (draws(cycle + 1, *x) && can_add));
+            }
+        }
+        let mut idx = sprite_positions.iter().position(|x| *x == 1).unwrap();
+        if !finishing[0][idx] {
+            continue;
+        }
+
+        let (mut lines, mut cycle) = (Vec::new(), 0);
+        while cycle < cycles {
+            let x = sprite_positions[idx];
+            let targets: Vec<usize> = (0..sprite_positions.len())
+                .filter(|target| finishing[cycle + 2][*target])
+                .collect();
+            let can_add = draws(cycle + 1, x) && !targets.is_empty();
+            if can_add && (!finishing[cycle + 1][idx] || rng.chance(70)) {
+                let target = *rng.choose(&targets);
+                lines.push(format!("addx {}", sprite_positions[target] - x));
+                idx = target;
+                cycle += 2;
             } else {
-                instructions.push(Instruction::Noop)
+                lines.push(String::from("noop"));
+                cycle += 1;
             }
         }
+        break lines;
+    };
 
-        Hanheld::new(instructions)
+    for _ in 0..rng.range(0..=size) {
+        lines.push(match rng.chance(30) {
+            true => String::from("noop"),
+            false => format!("addx {}", rng.range(-10..=10)),
+        });
     }
+    lines.join("\n").into()
 }
 
 #[cfg(test)]
@@ -176,178 +471,48 @@ mod tests {
     use super::*;
 
     #[test]
-    fn crt_render() {
-        TenthPuzzle {
-            puzzle: get_puzzle_info(),
-        }
-        .get_handheld()
-        .render();
-        assert!(true);
+    fn test_pattern() {
+        let handheld = TenthPuzzle::new(get_puzzle_info()).get_handheld().unwrap();
+        assert_eq!(
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....",
+            handheld.render().to_string()
+        );
     }
 
     #[test]
-    fn sum_of_signal_strenghts() {
+    fn beam_drawn_every_cycle() {
+        let puzzle = TenthPuzzle::new(get_puzzle_info());
+        let handheld = puzzle.get_handheld().unwrap();
+        let mut frames = Vec::new();
+        puzzle.frames(&handheld, Part::Second, &mut |frame| {
+            frames.push(frame.clone())
+        });
+        assert_eq!(240, frames.len());
         assert_eq!(
-            13140,
-            TenthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .get_handheld()
-            .sum_of_signal_strenghts()
-        )
+            "##=.....................................",
+            frames[1].to_string().lines().next().unwrap()
+        );
+        assert_eq!(
+            handheld.render().to_string(),
+            frames[239].to_string().replace(['@', '='], ".")
+        );
+    }
+
+    #[test]
+    fn unknown_instruction() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replacen("noop", "nop", 1);
+        let error = TenthPuzzle::new(puzzle).get_handheld().err().unwrap();
+        assert_eq!((10, 1), (error.line, error.column));
+        assert_eq!("`nop`", error.found);
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "addx 15
-                addx -11
-                addx 6
-                addx -3
-                addx 5
-                addx -1
-                addx -8
-                addx 13
-                addx 4
-                noop
-                addx -1
-                addx 5
-                addx -1
-                addx 5
-                addx -1
-                addx 5
-                addx -1
-                addx 5
-                addx -1
-                addx -35
-                addx 1
-                addx 24
-                addx -19
-                addx 1
-                addx 16
-                addx -11
-                noop
-                noop
-                addx 21
-                addx -15
-                noop
-                noop
-                addx -3
-                addx 9
-                addx 1
-                addx -3
-                addx 8
-                addx 1
-                addx 5
-                noop
-                noop
-                noop
-                noop
-                noop
-                addx -36
-                noop
-                addx 1
-                addx 7
-                noop
-                noop
-                noop
-                addx 2
-                addx 6
-                noop
-                noop
-                noop
-                noop
-                noop
-                addx 1
-                noop
-                noop
-                addx 7
-                addx 1
-                noop
-                addx -13
-                addx 13
-                addx 7
-                noop
-                addx 1
-                addx -33
-                noop
-                noop
-                noop
-                addx 2
-                noop
-                noop
-                noop
-                addx 8
-                noop
-                addx -1
-                addx 2
-                addx 1
-                noop
-                addx 17
-                addx -9
-                addx 1
-                addx 1
-                addx -3
-                addx 11
-                noop
-                noop
-                addx 1
-                noop
-                addx 1
-                noop
-                noop
-                addx -13
-                addx -19
-                addx 1
-                addx 3
-                addx 26
-                addx -30
-                addx 12
-                addx -1
-                addx 3
-                addx 1
-                noop
-                noop
-                noop
-                addx -9
-                addx 18
-                addx 1
-                addx 2
-                noop
-                noop
-                addx 9
-                noop
-                noop
-                noop
-                addx -1
-                addx 2
-                addx -37
-                addx 1
-                addx 3
-                noop
-                addx 15
-                addx -21
-                addx 22
-                addx -6
-                addx 1
-                noop
-                addx 2
-                addx 1
-                noop
-                addx -10
-                noop
-                noop
-                addx 20
-                addx 1
-                addx 2
-                addx 2
-                addx -6
-                addx -11
-                noop
-                noop
-                noop",
-            ),
-        }
+        PuzzleInfo::new(10, "Test", EXAMPLES[0].input)
     }
 }
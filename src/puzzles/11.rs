@@ -1,27 +1,103 @@
 use std::collections::HashMap;
 
 use itertools::Itertools;
-use regex::Regex;
+use regex::{Captures, Regex};
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::{InputLine, ParseError},
+    Answer, Example, Generated, Parameters, Part, Puzzle, PuzzleInfo, Rng, SolveError,
+};
 
 pub struct EleventhPuzzle {
     puzzle: PuzzleInfo,
+    params: EleventhParameters,
 }
 
-impl Solution for EleventhPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.get_jungle()
-                .monkey_business(20, CompressionKind::DivisonByThree),
-            self.get_jungle()
-                .monkey_business(10000, CompressionKind::ProductOfDivisors),
-        );
+parameters!(EleventhParameters {
+    first_rounds: u64 = 20,
+    second_rounds: u64 = 10000,
+});
+
+register!(
+    11,
+    "Eleventh Puzzle - Monkey in the Middle",
+    "11.txt",
+    EleventhPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1",
+    answers: [Some("10605"), Some("2713310158")],
+    params: &[],
+}];
+
+impl Puzzle for EleventhPuzzle {
+    // the monkeys holding their starting items, every part plays with its own copy
+    type Model = Jungle;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Jungle, ParseError> {
+        self.get_jungle()
+    }
+
+    fn summary(&self, jungle: &Jungle) -> String {
+        format!("{} monkeys", jungle.monkeys.len())
+    }
+
+    fn solve(&self, jungle: &Jungle, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(jungle
+                .clone()
+                .monkey_business(self.params.first_rounds, CompressionKind::DivisonByThree)
+                .into()),
+            Part::Second => Ok(jungle
+                .clone()
+                .monkey_business(
+                    self.params.second_rounds,
+                    CompressionKind::ProductOfDivisors,
+                )
+                .into()),
+        }
+    }
+
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        Some(&mut self.params)
     }
 }
 
-struct Jungle {
+#[derive(Clone)]
+pub struct Jungle {
     monkeys: HashMap<u8, Monkey>,
 }
 
@@ -45,11 +121,7 @@ impl Jungle {
         };
         for _ in 0..rounds {
             for i in 0..self.monkeys.len() as u8 {
-                loop {
-                    let monkey = match self.monkeys.get_mut(&i) {
-                        Some(m) => m,
-                        None => break,
-                    };
+                while let Some(monkey) = self.monkeys.get_mut(&i) {
                     let (item, receiver) =
                         match monkey.throw_to_another_monkey(compressor, &compression_kind) {
                             Some(t) => t,
@@ -77,6 +149,7 @@ enum CompressionKind {
     ProductOfDivisors,
 }
 
+#[derive(Clone)]
 struct Monkey {
     items: Vec<u64>,
     operation: Operation,
@@ -123,6 +196,7 @@ impl Monkey {
     }
 }
 
+#[derive(Clone)]
 enum Operation {
     Plus(u64),
     Multiply(u64),
@@ -139,6 +213,7 @@ impl Operation {
     }
 }
 
+#[derive(Clone)]
 struct TestDivisable {
     divisor: u64,
     success_receiver: u8,
@@ -156,65 +231,196 @@ impl TestDivisable {
 }
 
 impl EleventhPuzzle {
-    pub fn new() -> Self {
+    pub fn new(puzzle: PuzzleInfo) -> Self {
         Self {
-            puzzle: PuzzleInfo::new("Eleventh Puzzle - Monkey in the Middle", "./inputs/11.txt"),
+            puzzle,
+            params: EleventhParameters::default(),
         }
     }
 
-    fn get_jungle(&self) -> Jungle {
+    fn get_jungle(&self) -> Result<Jungle, ParseError> {
         let mut monkeys = Vec::new();
-        let descriptions: Vec<&str> = self
-            .puzzle
-            .input
-            .split("Monkey")
-            .filter(|e| !e.is_empty())
-            .collect();
-        let re_items = Regex::new(r".*Starting items:.*\n").unwrap();
+        let mut receivers = Vec::new();
+        let re_monkey = Regex::new(r"^Monkey (\d+):$").unwrap();
+        let re_items = Regex::new(r"^Starting items:( \d+(, \d+)*)?$").unwrap();
         let re_item_no = Regex::new(r"\d+").unwrap();
-        let re_operation = Regex::new(r".*old ([+*]) (old|\d+)").unwrap();
-        let re_test_divisor = Regex::new(r".*by (\d+)").unwrap();
-        let re_test_true = Regex::new(r".*true.* (\d+)").unwrap();
-        let re_test_false = Regex::new(r".*false.* (\d+)").unwrap();
+        let re_operation = Regex::new(r"^Operation: new = old ([+*]) (old|\d+)$").unwrap();
+        let re_test_divisor = Regex::new(r"^Test: divisible by (\d+)$").unwrap();
+        let re_test_true = Regex::new(r"^If true: throw to monkey (\d+)$").unwrap();
+        let re_test_false = Regex::new(r"^If false: throw to monkey (\d+)$").unwrap();
+
+        let mut lines = self
+            .puzzle
+            .lines()
+            .filter(|line| !line.text.is_empty())
+            .peekable();
 
-        for desc in descriptions.iter() {
+        while lines.peek().is_some() {
+            let monkey_id = monkeys.len();
+            let expected_monkey = format!("`Monkey {monkey_id}:`");
+            let (line, captures) = self.next_line(&mut lines, &re_monkey, &expected_monkey)?;
+            if line.parse::<usize>(7, &captures[1], &expected_monkey)? != monkey_id {
+                return Err(line.error(7, &format!("monkey id {monkey_id}")));
+            }
+
+            let (line, _) = self.next_line(&mut lines, &re_items, "`Starting items: N, N, ..`")?;
             let mut items = Vec::new();
-            let extracted_items_as_str = &re_items.captures(desc).unwrap()[0];
-            re_item_no
-                .captures_iter(extracted_items_as_str)
-                .for_each(|m| items.push(*&m[0].parse::<u64>().unwrap()));
+            for m in re_item_no.find_iter(line.text) {
+                items.push(line.parse::<u64>(m.start(), m.as_str(), "a worry level")?);
+            }
 
-            let operation_captured = re_operation.captures(desc).unwrap();
+            let (line, operation_captured) =
+                self.next_line(&mut lines, &re_operation, "`Operation: new = old [+*] N`")?;
             let operator = &operation_captured[1].chars().next().unwrap();
-            let operation_value = &operation_captured[2];
-            let operation = match operator {
-                '+' => match operation_value.parse::<u64>() {
-                    Ok(n) => Operation::Plus(n),
-                    Err(_) => unreachable!(),
-                },
-                '*' => match operation_value.parse::<u64>() {
-                    Ok(n) => Operation::Multiply(n),
-                    Err(_) => Operation::Square,
-                },
-                _ => unreachable!(),
+            let operation_value = operation_captured.get(2).unwrap();
+            let operation = match (operator, operation_value.as_str()) {
+                ('*', "old") => Operation::Square,
+                ('+', "old") => return Err(line.error(operation_value.start(), "a number")),
+                ('+', value) => {
+                    Operation::Plus(line.parse(operation_value.start(), value, "a number")?)
+                }
+                (_, value) => {
+                    Operation::Multiply(line.parse(operation_value.start(), value, "a number")?)
+                }
             };
 
-            let test_divisor_captured = re_test_divisor.captures(desc).unwrap();
-            let test_true_captured = re_test_true.captures(desc).unwrap();
-            let test_false_captured = re_test_false.captures(desc).unwrap();
-            let test = TestDivisable::new(
-                *&test_divisor_captured[1].parse().unwrap(),
-                *&test_true_captured[1].parse().unwrap(),
-                *&test_false_captured[1].parse().unwrap(),
-            );
+            let (line, test_divisor_captured) =
+                self.next_line(&mut lines, &re_test_divisor, "`Test: divisible by N`")?;
+            let divisor = line
+                .parse::<u64>(19, &test_divisor_captured[1], "a non-zero divisor")
+                .ok()
+                .filter(|divisor| *divisor != 0)
+                .ok_or_else(|| line.error(19, "a non-zero divisor"))?;
+
+            let mut test_receivers = [0; 2];
+            for (receiver, (re, expected)) in test_receivers.iter_mut().zip([
+                (&re_test_true, "`If true: throw to monkey N`"),
+                (&re_test_false, "`If false: throw to monkey N`"),
+            ]) {
+                let (line, captured) = self.next_line(&mut lines, re, expected)?;
+                let offset = captured.get(1).unwrap().start();
+                *receiver = line.parse(offset, &captured[1], "a monkey id")?;
+                receivers.push((monkey_id, *receiver, line, offset));
+            }
+            let test = TestDivisable::new(divisor, test_receivers[0], test_receivers[1]);
 
             monkeys.push(Monkey::new(items, operation, test));
         }
 
-        Jungle::new(monkeys)
+        if monkeys.len() < 2 {
+            return Err(self
+                .puzzle
+                .end_of_input_error(&format!("`Monkey {}:`", monkeys.len())));
+        }
+        // the monkeys can throw only to the other existing monkeys
+        for (monkey_id, receiver, line, offset) in receivers {
+            if receiver as usize >= monkeys.len() || receiver as usize == monkey_id {
+                return Err(line.error(
+                    offset,
+                    &format!("another monkey id between 0 and {}", monkeys.len() - 1),
+                ));
+            }
+        }
+
+        Ok(Jungle::new(monkeys))
+    }
+
+    fn next_line<'a>(
+        &self,
+        lines: &mut impl Iterator<Item = InputLine<'a>>,
+        re: &Regex,
+        expected: &str,
+    ) -> Result<(InputLine<'a>, Captures<'a>), ParseError> {
+        match lines.next() {
+            Some(line) => match re.captures(line.text) {
+                Some(captures) => Ok((line, captures)),
+                None => Err(line.error(0, expected)),
+            },
+            None => Err(self.puzzle.end_of_input_error(expected)),
+        }
     }
 }
 
+// monkeys throwing only to the other ones, the worry levels of the first part are not
+// bounded so the monkeys overflowing them within the rounds are thrown away
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let params = EleventhParameters::default();
+    let count = size.clamp(2, 8);
+    loop {
+        let mut divisors = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+        rng.shuffle(&mut divisors);
+        let monkeys: Vec<Monkey> = divisors[..count]
+            .iter()
+            .enumerate()
+            .map(|(id, divisor)| {
+                let items = (0..rng.range(0..=4)).map(|_| rng.range(50..=99)).collect();
+                let operation = match rng.range(0..=9) {
+                    0 => Operation::Square,
+                    1..=4 => Operation::Multiply(rng.range(2..=19)),
+                    _ => Operation::Plus(rng.range(1..=8)),
+                };
+                let mut receiver = || (id + rng.range(1..=count - 1)) % count;
+                let test = TestDivisable::new(*divisor, receiver() as u8, receiver() as u8);
+                Monkey::new(items, operation, test)
+            })
+            .collect();
+        if !overflows(&monkeys, params.first_rounds) {
+            return describe(&monkeys).into();
+        }
+    }
+}
+
+fn overflows(monkeys: &[Monkey], rounds: u64) -> bool {
+    let mut items: Vec<Vec<u64>> = monkeys.iter().map(|monkey| monkey.items.clone()).collect();
+    for _ in 0..rounds {
+        for (id, monkey) in monkeys.iter().enumerate() {
+            for item in std::mem::take(&mut items[id]) {
+                let item = match monkey.operation {
+                    Operation::Plus(n) => item.checked_add(n),
+                    Operation::Multiply(n) => item.checked_mul(n),
+                    Operation::Square => item.checked_mul(item),
+                };
+                let Some(item) = item.map(|item| item / 3) else {
+                    return true;
+                };
+                let receiver = match item % monkey.test.divisor {
+                    0 => monkey.test.success_receiver,
+                    _ => monkey.test.fail_receiver,
+                };
+                items[receiver as usize].push(item);
+            }
+        }
+    }
+    false
+}
+
+fn describe(monkeys: &[Monkey]) -> String {
+    let descriptions: Vec<String> = monkeys
+        .iter()
+        .enumerate()
+        .map(|(id, monkey)| {
+            let operation = match monkey.operation {
+                Operation::Plus(n) => format!("+ {n}"),
+                Operation::Multiply(n) => format!("* {n}"),
+                Operation::Square => String::from("* old"),
+            };
+            format!(
+                "Monkey {id}:
+  Starting items: {}
+  Operation: new = old {operation}
+  Test: divisible by {}
+    If true: throw to monkey {}
+    If false: throw to monkey {}",
+                monkey.items.iter().join(", "),
+                monkey.test.divisor,
+                monkey.test.success_receiver,
+                monkey.test.fail_receiver
+            )
+        })
+        .collect();
+    descriptions.join("\n\n")
+}
+
 #[cfg(test)]
 mod tests {
     use crate::PuzzleInfo;
@@ -222,61 +428,17 @@ mod tests {
     use super::*;
 
     #[test]
-    fn monkey_business_high_load() {
-        assert_eq!(
-            2713310158,
-            EleventhPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .get_jungle()
-            .monkey_business(10000, CompressionKind::ProductOfDivisors)
-        );
-    }
-
-    #[test]
-    fn monkey_business() {
-        assert_eq!(
-            10605,
-            EleventhPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .get_jungle()
-            .monkey_business(20, CompressionKind::DivisonByThree)
-        );
+    fn throwing_to_missing_monkey() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle
+            .input
+            .replace("throw to monkey 3", "throw to monkey 4");
+        let error = EleventhPuzzle::new(puzzle).get_jungle().err().unwrap();
+        assert_eq!((6, 31), (error.line, error.column));
+        assert_eq!("another monkey id between 0 and 3", error.expected);
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "Monkey 0:
-                Starting items: 79, 98
-                Operation: new = old * 19
-                Test: divisible by 23
-                  If true: throw to monkey 2
-                  If false: throw to monkey 3
-              
-              Monkey 1:
-                Starting items: 54, 65, 75, 74
-                Operation: new = old + 6
-                Test: divisible by 19
-                  If true: throw to monkey 2
-                  If false: throw to monkey 0
-              
-              Monkey 2:
-                Starting items: 79, 60, 97
-                Operation: new = old * old
-                Test: divisible by 13
-                  If true: throw to monkey 1
-                  If false: throw to monkey 3
-              
-              Monkey 3:
-                Starting items: 74
-                Operation: new = old + 3
-                Test: divisible by 17
-                  If true: throw to monkey 0
-                  If false: throw to monkey 1",
-            ),
-        }
+        PuzzleInfo::new(11, "Test", EXAMPLES[0].input)
     }
 }
@@ -1,17 +1,104 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, visualize::Pixel, Answer, Example, Frame, Generated, Grid, Part, Point2,
+    Puzzle, PuzzleInfo, Rng, SolveError, Visualize,
+};
 
 pub struct TwelfthPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for TwelfthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.fewer_steps_from_one_starting_location(),
-            self.fewer_steps_from_multiple_starting_locations(),
+register!(
+    12,
+    "Twelfth Puzzle - Hill Climbing Algorithm",
+    "12.txt",
+    TwelfthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi",
+    answers: [Some("31"), Some("29")],
+    params: &[],
+}];
+
+impl Puzzle for TwelfthPuzzle {
+    type Model = Grid<Elevation>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Grid<Elevation>, ParseError> {
+        self.read_heightmap()
+    }
+
+    fn summary(&self, heightmap: &Grid<Elevation>) -> String {
+        format!("{}x{} heightmap", heightmap.height(), heightmap.width())
+    }
+
+    fn solve(&self, heightmap: &Grid<Elevation>, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(self
+                .fewer_steps_from_one_starting_location(heightmap)?
+                .into()),
+            Part::Second => Ok(self
+                .fewer_steps_from_multiple_starting_locations(heightmap)?
+                .into()),
+        }
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize<Model = Grid<Elevation>>> {
+        Some(self)
+    }
+}
+
+const FRONTIER: [u8; 3] = [250, 220, 80];
+const DESTINATION: [u8; 3] = [240, 60, 60];
+
+impl Visualize for TwelfthPuzzle {
+    type Model = Grid<Elevation>;
+
+    // the search spreading from the starting locations one step after another, the elevation
+    // is shaded from dark to light
+    fn frames(&self, heightmap: &Grid<Elevation>, part: Part, sink: &mut dyn FnMut(&Frame)) {
+        let point = |location: &Location| Point2::new(location.x as i64, location.y as i64);
+        let shade = |location: &Location, visited: bool| {
+            let elevation = heightmap[*location];
+            let height = QItem::interpret_elevation(elevation) - b'a';
+            let color = match (elevation, visited) {
+                ('E', _) => DESTINATION,
+                (_, true) => [40, 70 + height * 5, 130 + height * 4],
+                (_, false) => [30 + height * 6, 50 + height * 7, 30 + height * 4],
+            };
+            Pixel::new(elevation, color)
+        };
+        let mut frame = Frame::new(heightmap.width(), heightmap.height());
+        for location in heightmap.positions() {
+            frame.set(point(&location), shade(&location, false));
+        }
+
+        let mut frontier: Vec<Location> = Vec::new();
+        self.bfs(
+            heightmap,
+            self.starting_locations(heightmap, part),
+            &mut |level| {
+                for location in &frontier {
+                    frame.set(point(location), shade(location, true));
+                }
+                for location in level {
+                    let pixel = Pixel::new(heightmap[*location], FRONTIER);
+                    frame.set(point(location), pixel);
+                }
+                frontier = level.to_vec();
+                sink(&frame);
+            },
         );
     }
 }
@@ -30,7 +117,7 @@ impl<T> Queue<T> {
     }
 
     fn dequeue(&mut self) -> Option<T> {
-        if self.items.len() > 0 {
+        if !self.items.is_empty() {
             return Some(self.items.remove(0));
         }
         None
@@ -43,133 +130,192 @@ impl<T> Queue<T> {
 
 struct QItem {
     location: Location,
-    movement: Movement,
+    elevation: Elevation,
     distance: u32,
 }
 
 impl QItem {
-    fn new(location: Location, movement: Movement, distance: u32) -> Self {
+    fn new(location: Location, elevation: Elevation, distance: u32) -> Self {
         Self {
             location,
-            movement,
+            elevation,
             distance,
         }
     }
 
     fn is_destination(&self) -> bool {
-        self.movement == 'E'
-    }
-
-    fn get_adjacent_locations(&self) -> Vec<Location> {
-        let mut adj_locations = vec![
-            (self.location.0, self.location.1 + 1),
-            (self.location.0 + 1, self.location.1),
-        ];
-        if self.location.0 > 0 {
-            adj_locations.push((self.location.0 - 1, self.location.1));
-        }
-        if self.location.1 > 0 {
-            adj_locations.push((self.location.0, self.location.1 - 1));
-        }
-
-        adj_locations
+        self.elevation == 'E'
     }
 
     fn interpret_elevation(elevation: char) -> u8 {
         match elevation {
-            'E' => 'z' as u8,
-            'S' => 'a' as u8,
+            'E' => b'z',
+            'S' => b'a',
             _ => elevation as u8,
         }
     }
 }
 
-type Location = (u8, u8);
-type Movement = char;
+pub type Location = Point2<usize>;
+pub type Elevation = char;
 
 impl TwelfthPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new(
-                "Twelfth Puzzle - Hill Climbing Algorithm",
-                "./inputs/12.txt",
-            ),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
 
-    fn fewer_steps_from_one_starting_location(&self) -> u32 {
-        let heightmap = self.read_heightmap();
-        let starting_location = heightmap.iter().find(|(_, value)| **value == 'S').unwrap();
-        let starting_location = (*starting_location.0, *starting_location.1);
-        self.bfs(&heightmap, starting_location).unwrap()
+    pub fn fewer_steps_from_one_starting_location(
+        &self,
+        heightmap: &Grid<Elevation>,
+    ) -> Result<u32, SolveError> {
+        let starting_locations = self.starting_locations(heightmap, Part::First);
+        self.bfs(heightmap, starting_locations, &mut |_| {})
+            .ok_or_else(|| {
+                SolveError::NoSolution(String::from(
+                    "The destination cannot be reached from the starting location",
+                ))
+            })
+    }
+
+    // a single search starting from all the lowest locations at once finds the nearest of them
+    pub fn fewer_steps_from_multiple_starting_locations(
+        &self,
+        heightmap: &Grid<Elevation>,
+    ) -> Result<u32, SolveError> {
+        let starting_locations = self.starting_locations(heightmap, Part::Second);
+        self.bfs(heightmap, starting_locations, &mut |_| {})
+            .ok_or_else(|| {
+                SolveError::NoSolution(String::from(
+                    "The destination cannot be reached from any of the lowest locations",
+                ))
+            })
     }
 
-    fn fewer_steps_from_multiple_starting_locations(&self) -> u32 {
-        let heightmap = self.read_heightmap();
+    fn starting_locations(
+        &self,
+        heightmap: &Grid<Elevation>,
+        part: Part,
+    ) -> Vec<(Location, Elevation)> {
         heightmap
             .iter()
-            .filter(|(_, value)| **value == 'S' || **value == 'a')
-            .map(|starting_loc| {
-                let starting_location = (*starting_loc.0, *starting_loc.1);
-                self.bfs(&heightmap, starting_location)
+            .filter(|(_, value)| match part {
+                Part::First => **value == 'S',
+                Part::Second => **value == 'S' || **value == 'a',
             })
-            .filter_map(|opt_result| opt_result)
-            .min()
-            .unwrap()
+            .map(|(location, elevation)| (location, *elevation))
+            .collect()
     }
 
-    fn bfs(
+    // the observer sees the locations reached in every step of the search
+    pub fn bfs(
         &self,
-        heightmap: &HashMap<Location, Movement>,
-        starting_location: (Location, Movement),
+        heightmap: &Grid<Elevation>,
+        starting_locations: Vec<(Location, Elevation)>,
+        observe: &mut dyn FnMut(&[Location]),
     ) -> Option<u32> {
         let mut visited_locations = HashSet::new();
         let mut queue = Queue::new();
+        let mut level = (0, Vec::new());
 
-        let start = QItem::new(starting_location.0, starting_location.1, 0);
-        visited_locations.insert(start.location);
-        queue.enqueue(start);
+        for (location, elevation) in starting_locations {
+            visited_locations.insert(location);
+            queue.enqueue(QItem::new(location, elevation, 0));
+        }
 
         while !queue.is_empty() {
             let item = queue.dequeue().unwrap();
 
+            if item.distance != level.0 {
+                observe(&level.1);
+                level = (item.distance, Vec::new());
+            }
+            level.1.push(item.location);
+
             if item.is_destination() {
+                observe(&level.1);
                 return Some(item.distance);
             }
 
-            for adjacent_location in item.get_adjacent_locations() {
-                match heightmap.get(&adjacent_location) {
-                    Some(movement) => {
-                        if QItem::interpret_elevation(item.movement) + 1
-                            >= QItem::interpret_elevation(*movement)
-                            && !visited_locations.contains(&adjacent_location)
-                        {
-                            queue.enqueue(QItem::new(
-                                adjacent_location,
-                                *movement,
-                                item.distance + 1,
-                            ));
-                            visited_locations.insert(adjacent_location);
-                        }
-                    }
-                    None => continue,
+            for (adjacent_location, elevation) in heightmap.neighbours(item.location) {
+                if QItem::interpret_elevation(item.elevation) + 1
+                    >= QItem::interpret_elevation(*elevation)
+                    && !visited_locations.contains(&adjacent_location)
+                {
+                    queue.enqueue(QItem::new(adjacent_location, *elevation, item.distance + 1));
+                    visited_locations.insert(adjacent_location);
                 }
             }
         }
+        if !level.1.is_empty() {
+            observe(&level.1);
+        }
         None
     }
 
-    fn read_heightmap(&self) -> HashMap<Location, Movement> {
-        let mut heightmap = HashMap::new();
+    pub fn read_heightmap(&self) -> Result<Grid<Elevation>, ParseError> {
+        let mut has_destination = false;
+        let mut has_start = false;
 
-        for (line_no, line) in self.puzzle.input.lines().enumerate() {
-            for (col_no, col) in line.trim().chars().enumerate() {
-                heightmap.insert((line_no as u8, col_no as u8), col);
+        let lines = self.puzzle.lines().filter(|line| !line.text.is_empty());
+        let heightmap = Grid::parse(lines, "locations", |line, offset, col| {
+            match col {
+                'S' if has_start => return Err(line.error(offset, "a single starting location")),
+                'S' => has_start = true,
+                'E' if has_destination => return Err(line.error(offset, "a single destination")),
+                'E' => has_destination = true,
+                'a'..='z' => {}
+                _ => return Err(line.error(offset, "an elevation a-z, S or E")),
             }
+            Ok(col)
+        })?;
+
+        if !has_start {
+            return Err(self.puzzle.end_of_input_error("a starting location `S`"));
+        }
+        if !has_destination {
+            return Err(self.puzzle.end_of_input_error("a destination `E`"));
         }
+        Ok(heightmap)
+    }
+}
 
-        heightmap
+// heightmap with a path from the start to the destination climbing at most one at a time,
+// the path goes only down and right so it never crosses itself
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let rows = size.clamp(10, 64);
+    let cols = (rows * 4).min(254);
+    let mut heightmap: Vec<Vec<char>> = (0..rows)
+        .map(|_| (0..cols).map(|_| rng.range(b'a'..=b'z') as char).collect())
+        .collect();
+
+    let start = (rng.range(0..=rows / 4), rng.range(0..=cols / 4));
+    let end = (
+        rng.range(rows * 3 / 4..=rows - 1),
+        rng.range(cols * 3 / 4..=cols - 1),
+    );
+    let mut steps: Vec<bool> = (start.0..end.0)
+        .map(|_| true)
+        .chain((start.1..end.1).map(|_| false))
+        .collect();
+    rng.shuffle(&mut steps);
+    // the elevation rises by one on the randomly chosen steps, reaching `z` at the destination
+    let mut climbs: Vec<bool> = (0..steps.len()).map(|idx| idx < 25).collect();
+    rng.shuffle(&mut climbs);
+
+    let (mut location, mut elevation) = (start, b'a');
+    heightmap[start.0][start.1] = 'S';
+    for (step_down, climb) in steps.into_iter().zip(climbs) {
+        match step_down {
+            true => location.0 += 1,
+            false => location.1 += 1,
+        }
+        elevation += climb as u8;
+        heightmap[location.0][location.1] = elevation as char;
     }
+    heightmap[end.0][end.1] = 'E';
+
+    let rows: Vec<String> = heightmap.into_iter().map(String::from_iter).collect();
+    rows.join("\n").into()
 }
 
 #[cfg(test)]
@@ -179,37 +325,66 @@ mod tests {
     use super::*;
 
     #[test]
-    fn fewer_steps_from_one_starting_location() {
-        assert_eq!(
-            31,
-            TwelfthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .fewer_steps_from_one_starting_location()
-        );
+    fn missing_destination() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace('E', "z");
+        let error = TwelfthPuzzle { puzzle }.read_heightmap().unwrap_err();
+        assert_eq!((6, 1), (error.line, error.column));
+        assert_eq!("a destination `E`", error.expected);
     }
 
     #[test]
-    fn fewer_steps_from_multiple_starting_locations() {
+    fn second_destination() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("abdefghi", "abdefghE");
+        let error = TwelfthPuzzle { puzzle }.read_heightmap().unwrap_err();
+        assert_eq!((5, 8), (error.line, error.column));
+        assert_eq!("a single destination", error.expected);
+    }
+
+    #[test]
+    fn unreachable_destination() {
+        let puzzle = TwelfthPuzzle::new(PuzzleInfo::new(12, "Test", "SzE"));
+        let heightmap = puzzle.read_heightmap().unwrap();
         assert_eq!(
-            29,
-            TwelfthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .fewer_steps_from_multiple_starting_locations()
+            Err(SolveError::NoSolution(String::from(
+                "The destination cannot be reached from the starting location"
+            ))),
+            puzzle.solve(&heightmap, Part::First)
         );
+        assert!(puzzle.solve(&heightmap, Part::Second).is_err());
     }
 
-    fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "Sabqponm
-abcryxxl
-accszExk
-acctuvwj
-abdefghi",
-            ),
+    #[test]
+    fn trailing_blank_line() {
+        let puzzle = TwelfthPuzzle::new(PuzzleInfo::new(12, "Test", "SbE\n\n"));
+        let heightmap = puzzle.read_heightmap().unwrap();
+        assert_eq!((3, 1), (heightmap.width(), heightmap.height()));
+    }
+
+    #[test]
+    fn uneven_rows() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("abdefghi", "abdefghij");
+        let error = TwelfthPuzzle { puzzle }.read_heightmap().unwrap_err();
+        assert_eq!((5, 9), (error.line, error.column));
+        assert_eq!("`j`", error.found);
+    }
+
+    #[test]
+    fn search_drawn_every_step() {
+        let puzzle = TwelfthPuzzle::new(get_puzzle_info());
+        let heightmap = puzzle.read_heightmap().unwrap();
+        for (part, steps) in [(Part::First, 31), (Part::Second, 29)] {
+            let mut frames = Vec::new();
+            puzzle.frames(&heightmap, part, &mut |frame| frames.push(frame.clone()));
+            assert_eq!(steps + 1, frames.len());
+            assert_eq!(EXAMPLES[0].input, frames[steps].to_string());
+            assert_eq!(FRONTIER, frames[steps].get(5, 2).color);
         }
     }
+
+    fn get_puzzle_info() -> PuzzleInfo {
+        PuzzleInfo::new(12, "Test", EXAMPLES[0].input)
+    }
 }
@@ -1,21 +1,81 @@
 use std::{cmp::Ordering, str::Chars};
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::{InputLine, ParseError},
+    Answer, Example, Generated, Part, Puzzle, PuzzleInfo, Rng, SolveError,
+};
 
 pub struct ThirteenthPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for ThirteenthPuzzle {
-    fn solution(&self) {
-        print_solution(&self.puzzle.name, self.sum_of_pairs_in_right_order(), self.decoder_key());
+register!(
+    13,
+    "Thirteenth Puzzle - Distress Signal",
+    "13.txt",
+    ThirteenthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "[1,1,3,1,1]
+[1,1,5,1,1]
+
+[[1],[2,3,4]]
+[[1],4]
+
+[9]
+[[8,7,6]]
+
+[[4,4],4,4]
+[[4,4],4,4,4]
+
+[7,7,7,7]
+[7,7,7]
+
+[]
+[3]
+
+[[[]]]
+[[]]
+
+[1,[2,[3,[4,[5,6,7]]]],8,9]
+[1,[2,[3,[4,[5,6,0]]]],8,9]",
+    answers: [Some("13"), Some("140")],
+    params: &[],
+}];
+
+impl Puzzle for ThirteenthPuzzle {
+    type Model = Vec<PacketPair>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Vec<PacketPair>, ParseError> {
+        self.read_packets()
+    }
+
+    fn summary(&self, packets: &Vec<PacketPair>) -> String {
+        format!("{} pairs of packets", packets.len())
+    }
+
+    fn solve(&self, packets: &Vec<PacketPair>, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(self.sum_of_pairs_in_right_order(packets).into()),
+            Part::Second => Ok(self.decoder_key(packets).into()),
+        }
     }
 }
 
-type PacketPair = (Packet, Packet);
+pub type PacketPair = (Packet, Packet);
+
+// the packets are parsed and compared recursively, the deeper ones would overflow the stack
+const MAX_DEPTH: usize = 100;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-enum Packet {
+pub enum Packet {
     Number(u8),
     List(Vec<Self>),
 }
@@ -31,19 +91,21 @@ impl Packet {
 
 impl PartialOrd for Packet {
     fn partial_cmp(&self, another_packet: &Packet) -> Option<Ordering> {
+        Some(self.cmp(another_packet))
+    }
+}
+
+impl Ord for Packet {
+    fn cmp(&self, another_packet: &Self) -> Ordering {
         match (self, another_packet) {
-            (Packet::Number(n), Packet::Number(m)) => n.partial_cmp(m),
-            (Packet::Number(n), packet) => {
-                Packet::List(vec![Packet::Number(*n)]).partial_cmp(packet)
-            }
-            (packet, Packet::Number(n)) => {
-                packet.partial_cmp(&Packet::List(vec![Packet::Number(*n)]))
-            }
+            (Packet::Number(n), Packet::Number(m)) => n.cmp(m),
+            (Packet::Number(n), packet) => Packet::List(vec![Packet::Number(*n)]).cmp(packet),
+            (packet, Packet::Number(n)) => packet.cmp(&Packet::List(vec![Packet::Number(*n)])),
             (Packet::List(left), Packet::List(right)) => {
-                let mut result = left.len().partial_cmp(&right.len());
+                let mut result = left.len().cmp(&right.len());
                 for (n, m) in left.iter().zip(right) {
-                    if n.partial_cmp(m).unwrap() != Ordering::Equal {
-                        result = n.partial_cmp(m);
+                    if n.cmp(m) != Ordering::Equal {
+                        result = n.cmp(m);
                         break;
                     }
                 }
@@ -53,21 +115,12 @@ impl PartialOrd for Packet {
     }
 }
 
-impl Ord for Packet {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
 impl ThirteenthPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new("Thirteenth Puzzle - Distress Signal", "./inputs/13.txt"),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
 
-    fn sum_of_pairs_in_right_order(&self) -> u32 {
-        let packets = self.read_packets();
+    pub fn sum_of_pairs_in_right_order(&self, packets: &[PacketPair]) -> u32 {
         let mut sum_of_pairs_in_right_order = 0;
 
         for (idx, (left_side, right_side)) in packets.iter().enumerate() {
@@ -78,12 +131,15 @@ impl ThirteenthPuzzle {
         sum_of_pairs_in_right_order as u32
     }
 
-    fn decoder_key(&self) -> u32 {
+    pub fn decoder_key(&self, packets: &[PacketPair]) -> u32 {
         let mut sorted_packets = Vec::new();
-        self.read_packets().into_iter().for_each(|(left, right)| {
-            sorted_packets.extend_from_slice(&[left, right]);
+        packets.iter().for_each(|(left, right)| {
+            sorted_packets.extend_from_slice(&[left.clone(), right.clone()]);
         });
-        let dividers = [Packet::List(vec![Packet::Number(2)]),Packet::List(vec![Packet::Number(6)])];
+        let dividers = [
+            Packet::List(vec![Packet::Number(2)]),
+            Packet::List(vec![Packet::Number(6)]),
+        ];
         sorted_packets.extend_from_slice(&dividers);
         sorted_packets.sort();
 
@@ -103,67 +159,100 @@ impl ThirteenthPuzzle {
         sum_of_pairs_in_right_order as u32
     }
 
-    fn read_packets(&self) -> Vec<PacketPair> {
-        let input: Vec<&str> = self
+    pub fn read_packets(&self) -> Result<Vec<PacketPair>, ParseError> {
+        let input: Vec<InputLine> = self
             .puzzle
-            .input
             .lines()
-            .map(|text| text.trim())
-            .filter(|text| !text.is_empty())
+            .filter(|line| !line.text.is_empty())
             .collect();
 
+        if !input.len().is_multiple_of(2) {
+            return Err(self
+                .puzzle
+                .end_of_input_error("the second packet of the pair"));
+        }
+
         input
             .chunks(2)
-            .map(|chunk| {
-                let first_chunk_iter = &mut chunk[0].chars();
-                first_chunk_iter.next();
-                first_chunk_iter.next_back();
-
-                let second_chunk_iter = &mut chunk[1].chars();
-                second_chunk_iter.next();
-                second_chunk_iter.next_back();
-                (
-                    self.read_packet(first_chunk_iter),
-                    self.read_packet(second_chunk_iter),
-                )
-            })
+            .map(|chunk| Ok((self.read_line(&chunk[0])?, self.read_line(&chunk[1])?)))
             .collect()
     }
 
-    fn read_packet(&self, iterator: &mut Chars) -> Packet {
+    fn read_line(&self, line: &InputLine) -> Result<Packet, ParseError> {
+        let iterator = &mut line.text.chars();
+        if iterator.next() != Some('[') {
+            return Err(line.error(0, "`[`"));
+        }
+        let packet = self.read_packet(line, iterator, 1)?;
+        if !iterator.as_str().is_empty() {
+            return Err(line.error(line.text.len() - iterator.as_str().len(), "end of line"));
+        }
+        Ok(packet)
+    }
+
+    fn read_packet(
+        &self,
+        line: &InputLine,
+        iterator: &mut Chars,
+        depth: usize,
+    ) -> Result<Packet, ParseError> {
         let mut packet = Packet::List(Vec::new());
         let mut acc_number = String::new();
-        loop {
-            let c = match iterator.next() {
-                Some(c) => c,
-                None => break,
-            };
+        // the offset of the character returned by the last call of next()
+        let offset = |iterator: &Chars| line.text.len() - iterator.as_str().len() - 1;
+        let number = |iterator: &Chars, acc_number: &str| {
+            let number_start = offset(iterator) - acc_number.len();
+            line.parse(number_start, acc_number, "a number 0-255")
+                .map(Packet::Number)
+        };
+
+        while let Some(c) = iterator.next() {
             match c {
+                '[' if depth == MAX_DEPTH => {
+                    let expected = format!("at most {MAX_DEPTH} nested lists");
+                    return Err(line.error(offset(iterator), &expected));
+                }
                 '[' => {
-                    packet.add(self.read_packet(iterator));
+                    packet.add(self.read_packet(line, iterator, depth + 1)?);
                 }
                 ']' => {
                     if !acc_number.is_empty() {
-                        packet.add(Packet::Number(acc_number.parse().unwrap()));
+                        packet.add(number(iterator, &acc_number)?);
                     }
-                    return packet;
+                    return Ok(packet);
                 }
                 ',' => {
                     if !acc_number.is_empty() {
-                        packet.add(Packet::Number(acc_number.parse().unwrap()));
+                        packet.add(number(iterator, &acc_number)?);
                         acc_number.clear();
                     }
                 }
-                _ => acc_number.push(c),
+                '0'..='9' => acc_number.push(c),
+                _ => return Err(line.error(offset(iterator), "a number, `,`, `[` or `]`")),
             };
         }
-        if !acc_number.is_empty() {
-            packet.add(Packet::Number(acc_number.parse().unwrap()));
-        }
-        packet
+        Err(line.error(line.text.len(), "`]`"))
     }
 }
 
+// pairs of the nested packets
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let pairs: Vec<String> = (0..size.max(1))
+        .map(|_| format!("{}\n{}", packet(rng, 0), packet(rng, 0)))
+        .collect();
+    pairs.join("\n\n").into()
+}
+
+fn packet(rng: &mut Rng, depth: usize) -> String {
+    let values: Vec<String> = (0..rng.range(0..=4))
+        .map(|_| match depth < 4 && rng.chance(30) {
+            true => packet(rng, depth + 1),
+            false => rng.range(0..=10).to_string(),
+        })
+        .collect();
+    format!("[{}]", values.join(","))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::PuzzleInfo;
@@ -171,55 +260,28 @@ mod tests {
     use super::*;
 
     #[test]
-    fn sum_of_pairs_in_right_order() {
-        assert_eq!(
-            13,
-            ThirteenthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .sum_of_pairs_in_right_order()
-        );
+    fn unbalanced_brackets() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("[[1],4]", "[[1],4");
+        let error = ThirteenthPuzzle { puzzle }.read_packets().unwrap_err();
+        assert_eq!((5, 7), (error.line, error.column));
+        assert_eq!("`]`", error.expected);
     }
 
     #[test]
-    fn decoder_key() {
-        assert_eq!(
-            140,
-            ThirteenthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .decoder_key()
-        );
+    fn deeply_nested_packet() {
+        let packet = "[".repeat(100000);
+        let puzzle = PuzzleInfo::new(13, "Test", &format!("{packet}\n[]"));
+        let error = ThirteenthPuzzle { puzzle }.read_packets().unwrap_err();
+        assert_eq!((1, 101), (error.line, error.column));
+        assert_eq!("at most 100 nested lists", error.expected);
+
+        let packet = format!("{}{}", "[".repeat(100), "]".repeat(100));
+        let puzzle = PuzzleInfo::new(13, "Test", &format!("{packet}\n[]"));
+        assert!(ThirteenthPuzzle { puzzle }.read_packets().is_ok());
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "[1,1,3,1,1]
-                [1,1,5,1,1]
-                
-                [[1],[2,3,4]]
-                [[1],4]
-                
-                [9]
-                [[8,7,6]]
-                
-                [[4,4],4,4]
-                [[4,4],4,4,4]
-                
-                [7,7,7,7]
-                [7,7,7]
-                
-                []
-                [3]
-                
-                [[[]]]
-                [[]]
-                
-                [1,[2,[3,[4,[5,6,7]]]],8,9]
-                [1,[2,[3,[4,[5,6,0]]]],8,9]",
-            ),
-        }
+        PuzzleInfo::new(13, "Test", EXAMPLES[0].input)
     }
 }
@@ -1,109 +1,202 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, iter};
 
-use itertools::Itertools;
 use regex::Regex;
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError,
+    visualize::{Pixel, Viewport},
+    Answer, Direction, Example, Frame, Generated, Parameters, Part, Point2, Puzzle, PuzzleInfo,
+    Rng, SolveError, Visualize,
+};
 
 pub struct FourteenthPuzzle {
     puzzle: PuzzleInfo,
+    params: FourteenthParameters,
 }
 
-impl Solution for FourteenthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.sand_coming_to_the_rest(FloorWidthKind::Determined),
-            self.sand_coming_to_the_rest(FloorWidthKind::Infinite),
-        );
+parameters!(FourteenthParameters {
+    // the sand is poured into the cave from the source
+    source_x: i16 = 500,
+    source_y: i16 = 0,
+});
+
+register!(
+    14,
+    "Fourteenth Puzzle - Regolith Reservoir",
+    "14.txt",
+    FourteenthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9",
+    answers: [Some("24"), Some("93")],
+    params: &[],
+}];
+
+impl Puzzle for FourteenthPuzzle {
+    // the positions of the rock
+    type Model = HashSet<Coordinate>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<HashSet<Coordinate>, ParseError> {
+        self.scan_path()
+    }
+
+    fn summary(&self, rock: &HashSet<Coordinate>) -> String {
+        let paths = self
+            .puzzle
+            .lines()
+            .filter(|line| !line.text.is_empty())
+            .count();
+        format!("{paths} rock paths, {} rock positions", rock.len())
+    }
+
+    fn solve(&self, rock: &HashSet<Coordinate>, part: Part) -> Result<Answer, SolveError> {
+        Ok(self
+            .sand_coming_to_the_rest(rock, self.source(), floor_kind(part), &mut |_| {})
+            .into())
+    }
+
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        Some(&mut self.params)
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize<Model = HashSet<Coordinate>>> {
+        Some(self)
     }
 }
 
-type Coordinate = (i16, i16);
+const ROCK: Pixel = Pixel::new('#', [140, 110, 90]);
+const SOURCE: Pixel = Pixel::new('+', [240, 60, 60]);
+const SAND: Pixel = Pixel::new('o', [230, 200, 110]);
+
+impl Visualize for FourteenthPuzzle {
+    type Model = HashSet<Coordinate>;
+
+    // the cave after every grain of sand coming to rest
+    fn frames(&self, rock: &HashSet<Coordinate>, part: Part, sink: &mut dyn FnMut(&Frame)) {
+        let point =
+            |coordinate: Coordinate| Point2::<i64>::new(coordinate.x.into(), coordinate.y.into());
+        let source = point(self.source());
+        let (mut top_left, mut bottom_right) = (source, source);
+        for &coordinate in rock {
+            let Point2 { x, y } = point(coordinate);
+            top_left = Point2::new(x.min(top_left.x), y.min(top_left.y));
+            bottom_right = Point2::new(x.max(bottom_right.x), y.max(bottom_right.y));
+        }
+        // the sand on the floor spreads into a triangle below the source
+        let floor = bottom_right.y + 2;
+        if let Part::Second = part {
+            let spread = floor - source.y;
+            top_left.x = top_left.x.min(source.x - spread);
+            bottom_right = Point2::new(bottom_right.x.max(source.x + spread), floor);
+        }
+
+        let viewport = Viewport::new(top_left, bottom_right, 200);
+        let mut frame = viewport.frame();
+        for &coordinate in rock {
+            frame.set(viewport.project(point(coordinate)), ROCK);
+        }
+        if let Part::Second = part {
+            for x in top_left.x..=bottom_right.x {
+                frame.set(viewport.project(Point2::new(x, floor)), ROCK);
+            }
+        }
+        frame.set(viewport.project(source), SOURCE);
+
+        self.sand_coming_to_the_rest(rock, self.source(), floor_kind(part), &mut |grain| {
+            frame.set(viewport.project(point(grain)), SAND);
+            sink(&frame);
+        });
+    }
+}
+
+type Coordinate = Point2<i16>;
 
 trait FillLine {
     fn bridge_the_gap(&self, coordinate: &Coordinate) -> Vec<Coordinate>;
 }
 
 impl FillLine for Coordinate {
+    // the points between the two in the same row or column, not including them, the walk
+    // stops at the other point so it does not step beyond the range of the coordinates
     fn bridge_the_gap(&self, coordinate: &Coordinate) -> Vec<Coordinate> {
-        let mut missing_coordinates = vec![];
-        let increment_or_decrement = |first: i16, second: i16| {
-            if first > second {
-                first - 1
-            } else {
-                first + 1
-            }
-        };
-        let get_missing_coordinate = |first: &Coordinate, second: &Coordinate| {
-            let is_line_gap = (first.0 - second.0).abs() > 1;
-            let is_col_gap = (first.1 - second.1).abs() > 1;
-
-            if is_line_gap {
-                Some((increment_or_decrement(first.0, second.0), first.1))
-            } else if is_col_gap {
-                Some((first.0, increment_or_decrement(first.1, second.1)))
-            } else {
-                None
-            }
-        };
-
-        match get_missing_coordinate(self, coordinate) {
-            Some(c) => {
-                missing_coordinates.push(c);
-                loop {
-                    match get_missing_coordinate(missing_coordinates.last().unwrap(), coordinate) {
-                        Some(mc) => missing_coordinates.push(mc),
-                        None => break,
-                    };
-                }
-
-                missing_coordinates
-            }
-            None => missing_coordinates,
-        }
+        let step = (*coordinate - *self).signum();
+        iter::successors(Some(*self + step), |missing| {
+            (missing != coordinate).then(|| *missing + step)
+        })
+        .take_while(|missing| missing != coordinate)
+        .collect()
     }
 }
 
 struct Triangle {
     coordinates: HashSet<Coordinate>,
     height: i16,
+    source: Coordinate,
     floor_kind: FloorWidthKind,
 }
 
 impl Triangle {
-    fn new(coordinates: HashSet<Coordinate>, floor_kind: FloorWidthKind) -> Self {
-        let mut height = *coordinates.iter().map(|(_, y)| y).max().unwrap();
+    fn new(
+        coordinates: HashSet<Coordinate>,
+        source: Coordinate,
+        floor_kind: FloorWidthKind,
+    ) -> Self {
+        let mut height = coordinates
+            .iter()
+            .map(|coordinate| coordinate.y)
+            .max()
+            .unwrap();
 
-        match floor_kind {
-            FloorWidthKind::Infinite => height += 2,
-            _ => {}
+        // the floor lies below the source as well
+        if let FloorWidthKind::Infinite = floor_kind {
+            height = height.max(source.y) + 2;
         }
 
         Self {
             coordinates,
             height,
+            source,
             floor_kind,
         }
     }
 
-    fn spread_the_coordinates(&mut self) {
-        let starting_height = *self
+    // the observer sees every grain coming to rest, without any rock right below the source
+    // and without the floor the first grain falls into the abyss already
+    fn spread_the_coordinates(&mut self, observe: &mut dyn FnMut(Coordinate)) {
+        let rock_below = self
             .coordinates
             .iter()
-            .filter(|(x, _)| *x == 500)
-            .map(|(_, y)| y)
-            .min()
-            .unwrap();
-        let mut starting_coordinate: Coordinate = (500, starting_height - 1);
+            .filter(|coordinate| coordinate.x == self.source.x && coordinate.y > self.source.y)
+            .map(|coordinate| coordinate.y)
+            .min();
+        let starting_height = match (rock_below, &self.floor_kind) {
+            (Some(y), _) => y,
+            (None, FloorWidthKind::Infinite) => self.height,
+            (None, FloorWidthKind::Determined) => return,
+        };
+        let mut starting_coordinate = Point2::new(self.source.x, starting_height - 1);
 
         loop {
-            match starting_coordinate.spread(&self.coordinates, self.height, &self.floor_kind) {
+            match starting_coordinate.spread(
+                &self.coordinates,
+                self.height,
+                self.source,
+                &self.floor_kind,
+            ) {
                 Ok(c) => {
                     self.coordinates.insert(c);
+                    observe(c);
                 }
                 Err(kind) => match kind {
-                    ErrorKind::NotSpace => starting_coordinate.1 -= 1,
+                    ErrorKind::NotSpace => starting_coordinate.y -= 1,
                     _ => break,
                 },
             }
@@ -116,10 +209,9 @@ trait Sand {
         &self,
         existing_tiles: &HashSet<Coordinate>,
         max_height: i16,
+        source: Coordinate,
         floor_kind: &FloorWidthKind,
     ) -> Result<Coordinate, ErrorKind>;
-
-    fn movement(&self, kind: MoveKind) -> Coordinate;
 }
 
 impl Sand for Coordinate {
@@ -127,31 +219,34 @@ impl Sand for Coordinate {
         &self,
         existing_tiles: &HashSet<Coordinate>,
         max_height: i16,
+        source: Coordinate,
         floor_kind: &FloorWidthKind,
     ) -> Result<Coordinate, ErrorKind> {
-        let left_occupied = existing_tiles.contains(&(self.0 - 1, self.1 + 1));
-        let right_occupied = existing_tiles.contains(&(self.0 + 1, self.1 + 1));
-        let down_occupied = existing_tiles.contains(&(self.0, self.1 + 1));
+        let occupied =
+            |direction: Direction| existing_tiles.contains(&(*self + direction.offset()));
+        let left_occupied = occupied(Direction::DownLeft);
+        let right_occupied = occupied(Direction::DownRight);
+        let down_occupied = occupied(Direction::Down);
         let self_contained = existing_tiles.contains(self);
 
-        if self.1 > max_height {
+        if self.y > max_height {
             match floor_kind {
                 FloorWidthKind::Determined => return Err(ErrorKind::FallingForever),
                 FloorWidthKind::Infinite => return Err(ErrorKind::NotSpace),
             }
         }
 
-        if *self == (500, 0) && self_contained {
+        if *self == source && self_contained {
             return Err(ErrorKind::SourceBlocked);
         }
 
-        match floor_kind {
-            FloorWidthKind::Infinite => {
-                if self.1 + 1 == max_height {
-                    return Ok(*self);
-                }
+        if let FloorWidthKind::Infinite = floor_kind {
+            if self.y + 1 == max_height {
+                return match self_contained {
+                    true => Err(ErrorKind::NotSpace),
+                    false => Ok(*self),
+                };
             }
-            _ => {}
         }
 
         if left_occupied && right_occupied && down_occupied {
@@ -162,30 +257,16 @@ impl Sand for Coordinate {
             }
         }
 
-        let next_coordinate = if !down_occupied {
-            self.movement(MoveKind::Down)
+        let direction = if !down_occupied {
+            Direction::Down
         } else if !left_occupied {
-            self.movement(MoveKind::LeftDown)
+            Direction::DownLeft
         } else {
-            self.movement(MoveKind::RightDown)
+            Direction::DownRight
         };
 
-        next_coordinate.spread(existing_tiles, max_height, floor_kind)
+        (*self + direction.offset()).spread(existing_tiles, max_height, source, floor_kind)
     }
-
-    fn movement(&self, kind: MoveKind) -> Coordinate {
-        match kind {
-            MoveKind::LeftDown => (self.0 - 1, self.1 + 1),
-            MoveKind::RightDown => (self.0 + 1, self.1 + 1),
-            MoveKind::Down => (self.0, self.1 + 1),
-        }
-    }
-}
-
-enum MoveKind {
-    LeftDown,
-    RightDown,
-    Down,
 }
 
 enum ErrorKind {
@@ -199,83 +280,242 @@ enum FloorWidthKind {
     Determined,
 }
 
+// the sand falls into the abyss in the first part and onto the floor in the second one
+fn floor_kind(part: Part) -> FloorWidthKind {
+    match part {
+        Part::First => FloorWidthKind::Determined,
+        Part::Second => FloorWidthKind::Infinite,
+    }
+}
+
 impl FourteenthPuzzle {
-    pub fn new() -> Self {
+    pub fn new(puzzle: PuzzleInfo) -> Self {
         Self {
-            puzzle: PuzzleInfo::new("Fourteenth Puzzle - Regolith Reservoir", "./inputs/14.txt"),
+            puzzle,
+            params: FourteenthParameters::default(),
         }
     }
 
-    fn sand_coming_to_the_rest(&self, floor_kind: FloorWidthKind) -> usize {
-        let coordinates = self.scan_path();
-        let origin_len = coordinates.len();
-        let mut triangle = Triangle::new(coordinates, floor_kind);
-        triangle.spread_the_coordinates();
-        triangle.coordinates.len() - origin_len
+    fn source(&self) -> Coordinate {
+        Point2::new(self.params.source_x, self.params.source_y)
+    }
+
+    fn sand_coming_to_the_rest(
+        &self,
+        rock: &HashSet<Coordinate>,
+        source: Coordinate,
+        floor_kind: FloorWidthKind,
+        observe: &mut dyn FnMut(Coordinate),
+    ) -> usize {
+        let mut triangle = Triangle::new(rock.clone(), source, floor_kind);
+        triangle.spread_the_coordinates(observe);
+        triangle.coordinates.len() - rock.len()
     }
 
-    fn scan_path(&self) -> HashSet<Coordinate> {
-        let re_number = Regex::new(r"(\d+,\d+)").unwrap();
+    fn scan_path(&self) -> Result<HashSet<Coordinate>, ParseError> {
+        let re_number = Regex::new(r"^(\d+),(\d+)$").unwrap();
         let mut coordinates = HashSet::new();
-        self.puzzle.input.lines().for_each(|line| {
+        for line in self.puzzle.lines().filter(|line| !line.text.is_empty()) {
             let mut path: Vec<Coordinate> = vec![];
-            let line = line.trim();
-            re_number.captures_iter(line).for_each(|c| {
-                let coordinate = c[0]
-                    .split_terminator(",")
-                    .map(|n| n.parse().unwrap())
-                    .next_tuple()
-                    .unwrap();
-                if path.is_empty() {
-                    path.push(coordinate);
-                } else {
-                    path.extend(coordinate.bridge_the_gap(path.last().unwrap()));
-                    path.push(coordinate);
+            let mut offset = 0;
+            for point in line.text.split(" -> ") {
+                let c = re_number
+                    .captures(point)
+                    .ok_or_else(|| line.error(offset, "a point like `498,4`"))?;
+                let coordinate: Coordinate = Point2::new(
+                    line.parse(offset, &c[1], "an x coordinate")?,
+                    line.parse(offset + c[1].len() + 1, &c[2], "a y coordinate")?,
+                );
+                match path.last().copied() {
+                    None => path.push(coordinate),
+                    Some(last) if last.x != coordinate.x && last.y != coordinate.y => {
+                        return Err(line.error(offset, "a point in line with the previous one"));
+                    }
+                    Some(last) => {
+                        path.extend(coordinate.bridge_the_gap(&last));
+                        path.push(coordinate);
+                    }
                 }
-            });
+                offset += point.len() + " -> ".len();
+            }
             coordinates.extend(path);
-        });
+        }
 
-        coordinates
+        if coordinates.is_empty() {
+            return Err(self.puzzle.end_of_input_error("a path of rock"));
+        }
+        Ok(coordinates)
     }
 }
 
+// paths of rock below the source, they may miss the column of the source so that the sand
+// falls into the abyss right away
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let params = FourteenthParameters::default();
+    let source = (params.source_x, params.source_y);
+    let depth = (10 + size.min(280) / 2) as i16;
+    let mut paths = Vec::new();
+    for _ in 0..size.max(1) {
+        let mut point = (
+            rng.range(source.0 - depth..=source.0 + depth),
+            rng.range(source.1 + 1..=source.1 + depth),
+        );
+        let mut path = vec![format!("{},{}", point.0, point.1)];
+        let horizontal = rng.chance(50);
+        for idx in 0..rng.range(1..=4) {
+            let length = rng.range(-6..=6);
+            match (idx % 2 == 0) == horizontal {
+                true => point.0 += length,
+                false => point.1 = (point.1 + length).clamp(source.1 + 1, source.1 + depth),
+            }
+            path.push(format!("{},{}", point.0, point.1));
+        }
+        paths.push(path.join(" -> "));
+    }
+    paths.join("\n").into()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::PuzzleInfo;
+    use crate::{property, PuzzleInfo, Solution};
 
     use super::*;
 
     #[test]
-    fn sand_to_rest_till_source_is_blocked() {
+    fn matches_reference() {
+        // the few paths of the small inputs often miss the column of the source
+        for size in [3, 20] {
+            property::check(&REGISTRATION, 20, size, &matches);
+        }
+    }
+
+    fn matches(generated: &Generated, puzzle: &dyn Solution) -> Result<(), String> {
+        let (abyss, floor) = reference(&generated.input);
+        property::compare(puzzle, Part::First, abyss)?;
+        property::compare(puzzle, Part::Second, floor)
+    }
+
+    #[test]
+    fn no_rock_below_the_source() {
+        let mut puzzle = FourteenthPuzzle::new(get_puzzle_info());
+        puzzle.params.source_x = 0;
+        let rock = puzzle.scan_path().unwrap();
+        assert_eq!(Ok(Answer::from(0)), puzzle.solve(&rock, Part::First));
+        // the grains pile up on the floor into a triangle of the height of the floor
+        assert_eq!(Ok(Answer::from(121)), puzzle.solve(&rock, Part::Second));
+    }
+
+    #[test]
+    fn sand_drawn_every_grain() {
+        let puzzle = FourteenthPuzzle::new(get_puzzle_info());
+        let rock = puzzle.scan_path().unwrap();
+        let mut frames = Vec::new();
+        puzzle.frames(&rock, Part::First, &mut |frame| {
+            frames.push(frame.to_string())
+        });
+        assert_eq!(24, frames.len());
         assert_eq!(
-            93,
-            FourteenthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .sand_coming_to_the_rest(FloorWidthKind::Infinite)
+            "......+...
+..........
+......o...
+.....ooo..
+....#ooo##
+...o#ooo#.
+..###ooo#.
+....oooo#.
+.o.ooooo#.
+#########.",
+            frames[23]
+        );
+
+        frames.clear();
+        puzzle.frames(&rock, Part::Second, &mut |frame| {
+            frames.push(frame.to_string())
+        });
+        assert_eq!(93, frames.len());
+        assert_eq!(
+            "...........o...........
+..........ooo..........
+.........ooooo.........
+........ooooooo........
+.......oo#ooo##o.......
+......ooo#ooo#ooo......
+.....oo###ooo#oooo.....
+....oooo.oooo#ooooo....
+...oooooooooo#oooooo...
+..ooo#########ooooooo..
+.ooooo.......ooooooooo.
+#######################",
+            frames[92]
         );
     }
 
     #[test]
-    fn sand_coming_to_the_rest() {
-        assert_eq!(
-            24,
-            FourteenthPuzzle {
-                puzzle: get_puzzle_info(),
+    fn diagonal_path() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("498,6 -> 496,6", "498,6 -> 496,7");
+        let error = FourteenthPuzzle::new(puzzle).scan_path().unwrap_err();
+        assert_eq!((1, 19), (error.line, error.column));
+        assert_eq!("`496,7`", error.found);
+    }
+
+    #[test]
+    fn path_to_the_edge_of_the_range() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = String::from("32765,12 -> 32767,12");
+        let rock = FourteenthPuzzle::new(puzzle).scan_path().unwrap();
+        assert_eq!(3, rock.len());
+    }
+
+    // the sand is dropped grain by grain into the cave, first until a grain falls below all the
+    // rock and then again onto the floor until the source is blocked
+    fn reference(input: &str) -> (usize, usize) {
+        let mut rock = HashSet::new();
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let points: Vec<(i32, i32)> = line
+                .split(" -> ")
+                .map(|point| {
+                    let (x, y) = point.split_once(',').unwrap();
+                    (x.parse().unwrap(), y.parse().unwrap())
+                })
+                .collect();
+            for pair in points.windows(2) {
+                let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+                for x in x1.min(x2)..=x1.max(x2) {
+                    for y in y1.min(y2)..=y1.max(y2) {
+                        rock.insert((x, y));
+                    }
+                }
             }
-            .sand_coming_to_the_rest(FloorWidthKind::Determined)
-        );
+        }
+        let lowest = rock.iter().map(|(_, y)| *y).max().unwrap();
+        let pour = |floor: Option<i32>| {
+            let mut blocked = rock.clone();
+            let mut grains = 0;
+            while !blocked.contains(&(500, 0)) {
+                let mut grain = (500, 0);
+                loop {
+                    let next = [0, -1, 1]
+                        .map(|dx| (grain.0 + dx, grain.1 + 1))
+                        .into_iter()
+                        .find(|next| !blocked.contains(next) && Some(next.1) != floor);
+                    match next {
+                        Some(next) => grain = next,
+                        None => break,
+                    }
+                    if floor.is_none() && grain.1 > lowest {
+                        return grains;
+                    }
+                }
+                blocked.insert(grain);
+                grains += 1;
+            }
+            grains
+        };
+        (pour(None), pour(Some(lowest + 2)))
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "498,4 -> 498,6 -> 496,6
-                503,4 -> 502,4 -> 502,9 -> 494,9
-                ",
-            ),
-        }
+        PuzzleInfo::new(14, "Test", EXAMPLES[0].input)
     }
 }
@@ -1,280 +1,559 @@
+use std::num::NonZeroI64;
+
 use itertools::Itertools;
 use regex::Regex;
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError,
+    visualize::{Pixel, Viewport},
+    Answer, Direction, Example, Frame, Generated, Interval, IntervalSet, Parameters, Part, Point2,
+    Puzzle, PuzzleInfo, Rng, SolveError, Visualize,
+};
 
 pub struct FifteenthPuzzle {
     puzzle: PuzzleInfo,
+    params: FifteenthParameters,
 }
 
-impl Solution for FifteenthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.read_sensors().positions_not_containing_beacon(2000000),
-            self.read_sensors().tuning_frequency(),
-        );
+parameters!(FifteenthParameters {
+    // the row searched for the positions without a beacon in the first part
+    row: Y = 2000000,
+    // the distress beacon is searched within 0..=bound for both coordinates
+    bound: i32 = 4000000,
+    // the x coordinate of the distress beacon is multiplied by it for the tuning frequency
+    multiplier: NonZeroI64 = NonZeroI64::new(4000000).unwrap(),
+});
+
+register!(
+    15,
+    "Fifteenth Puzzle - Beacon Exclusion Zone",
+    "15.txt",
+    FifteenthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+Sensor at x=9, y=16: closest beacon is at x=10, y=16
+Sensor at x=13, y=2: closest beacon is at x=15, y=3
+Sensor at x=12, y=14: closest beacon is at x=10, y=16
+Sensor at x=10, y=20: closest beacon is at x=10, y=16
+Sensor at x=14, y=17: closest beacon is at x=10, y=16
+Sensor at x=8, y=7: closest beacon is at x=2, y=10
+Sensor at x=2, y=0: closest beacon is at x=2, y=10
+Sensor at x=0, y=11: closest beacon is at x=2, y=10
+Sensor at x=20, y=14: closest beacon is at x=25, y=17
+Sensor at x=17, y=20: closest beacon is at x=21, y=22
+Sensor at x=16, y=7: closest beacon is at x=15, y=3
+Sensor at x=14, y=3: closest beacon is at x=15, y=3
+Sensor at x=20, y=1: closest beacon is at x=15, y=3",
+    answers: [Some("26"), Some("56000011")],
+    params: &[("row", "10"), ("bound", "20")],
+}];
+
+impl Puzzle for FifteenthPuzzle {
+    type Model = Area;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
     }
-}
 
-type Coordinate = (X, Y);
+    fn parse(&self) -> Result<Area, ParseError> {
+        self.read_sensors()
+    }
 
-trait CoordinateMerging {
-    fn merge(&mut self, next_coordinate: Coordinate) -> Result<(), ()>;
-}
+    fn summary(&self, area: &Area) -> String {
+        format!("{} sensors", area.sensors.len())
+    }
 
-impl CoordinateMerging for Coordinate {
-    fn merge(&mut self, next_coordinate: Self) -> Result<(), ()> {
-        if next_coordinate.0 - 1 <= self.1 {
-            if self.0 > next_coordinate.0 {
-                self.0 = next_coordinate.0;
-            }
-            if self.1 < next_coordinate.1 {
-                self.1 = next_coordinate.1;
-            }
-            Ok(())
-        } else {
-            Err(())
+    fn solve(&self, area: &Area, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(area.positions_not_containing_beacon(self.params.row).into()),
+            Part::Second => Ok(area
+                .tuning_frequency(self.params.bound, self.params.multiplier.get())?
+                .into()),
         }
     }
-}
 
-type X = i32;
-type Y = i32;
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        Some(&mut self.params)
+    }
 
-struct Rhombus {
-    center: Coordinate,
-    coverage: i32,
+    fn visualization(&self) -> Option<&dyn Visualize<Model = Area>> {
+        Some(self)
+    }
 }
 
-impl Rhombus {
-    fn new(sensor: Coordinate, beacon: Coordinate) -> Self {
-        Self {
-            center: sensor,
-            coverage: Self::calculate_coverage(sensor, beacon),
-        }
-    }
+// the coverage of the neighbouring sensors is told apart by the color
+const COVERAGE: [[u8; 3]; 4] = [[50, 90, 60], [60, 70, 110], [100, 80, 50], [90, 60, 100]];
+const SENSOR: Pixel = Pixel::new('S', [90, 200, 240]);
+const BEACON: Pixel = Pixel::new('B', [240, 220, 90]);
+const ROW: Pixel = Pixel::new('=', [250, 150, 40]);
+const DISTRESS_BEACON: Pixel = Pixel::new('X', [250, 60, 60]);
+
+impl Visualize for FifteenthPuzzle {
+    type Model = Area;
+
+    // the area covered by the sensors one after another, followed by the searched row in the
+    // first part and by the distress beacon in the second one, the large areas are scaled down
+    fn frames(&self, area: &Area, part: Part, sink: &mut dyn FnMut(&Frame)) {
+        let point =
+            |coordinate: Coordinate| Point2::<i64>::new(coordinate.x.into(), coordinate.y.into());
+        let searched = match part {
+            Part::First => vec![Point2::new(area.sensors[0].x, self.params.row)],
+            Part::Second => vec![
+                Point2::new(0, 0),
+                Point2::new(self.params.bound, self.params.bound),
+            ],
+        };
+        let (top_left, bottom_right) = searched
+            .iter()
+            .chain(&area.sensors)
+            .chain(&area.beacons)
+            .map(|coordinate| point(*coordinate))
+            .fold(
+                (
+                    Point2::new(i64::MAX, i64::MAX),
+                    Point2::new(i64::MIN, i64::MIN),
+                ),
+                |(min, max), Point2 { x, y }| {
+                    (
+                        Point2::new(min.x.min(x), min.y.min(y)),
+                        Point2::new(max.x.max(x), max.y.max(y)),
+                    )
+                },
+            );
+        let viewport = Viewport::new(top_left, bottom_right, 200);
+        let covered = |rhombus: &Rhombus, position: Point2<i64>| {
+            rhombus.center.manhattan(position) <= rhombus.coverage
+        };
 
-    fn calculate_coverage(first_coordinate: Coordinate, second_coordinate: Coordinate) -> i32 {
-        (first_coordinate.0 - second_coordinate.0).abs()
-            + (first_coordinate.1 - second_coordinate.1).abs()
-    }
+        let mut frame = viewport.frame();
+        for (idx, rhombus) in area.rhombuses.iter().enumerate() {
+            let pixel = Pixel::new('#', COVERAGE[idx % COVERAGE.len()]);
+            for y in 0..frame.height() {
+                for x in 0..frame.width() {
+                    if covered(rhombus, viewport.unproject(x, y)) {
+                        frame.set(Point2::new(x as i64, y as i64), pixel);
+                    }
+                }
+            }
+            for (sensor, beacon) in area.sensors.iter().zip(&area.beacons) {
+                frame.set(viewport.project(point(*sensor)), SENSOR);
+                frame.set(viewport.project(point(*beacon)), BEACON);
+            }
+            sink(&frame);
+        }
 
-    fn calculate_boundary_with_restriction(
-        &self,
-        value: i32,
-        restriction_value: i32,
-        restriction_kind: RestrictionKind,
-    ) -> i32 {
-        match restriction_kind {
-            RestrictionKind::GreaterEq => {
-                if value < restriction_value {
-                    restriction_value
-                } else {
-                    value
+        match part {
+            Part::First => {
+                let row = i64::from(self.params.row);
+                let y = viewport.project(Point2::new(0, row)).y;
+                for x in 0..frame.width() {
+                    let position = Point2::new(viewport.unproject(x, 0).x, row);
+                    // the beacons on the row are not counted
+                    if frame.get(x, y as usize) != BEACON
+                        && area
+                            .rhombuses
+                            .iter()
+                            .any(|rhombus| covered(rhombus, position))
+                    {
+                        frame.set(Point2::new(x as i64, y), ROW);
+                    }
                 }
             }
-            RestrictionKind::LessEq => {
-                if value > restriction_value {
-                    restriction_value
-                } else {
-                    value
+            Part::Second => {
+                // the position is decoded from the tuning frequency
+                let multiplier = self.params.multiplier.get();
+                if let Ok(frequency) = area.tuning_frequency(self.params.bound, multiplier) {
+                    let distress = Point2::new(
+                        frequency.div_euclid(multiplier),
+                        frequency.rem_euclid(multiplier),
+                    );
+                    frame.set(viewport.project(distress), DISTRESS_BEACON);
                 }
             }
         }
+        sink(&frame);
     }
+}
 
-    fn get_x_coordinates_for_y(
-        &self,
-        y: Y,
-        x_restriction: Option<Coordinate>,
-    ) -> Option<Coordinate> {
-        let diff = (self.center.1 - y).abs();
-        let coverage = self.coverage - diff;
+pub type Coordinate = Point2<i32>;
+pub type X = i32;
+pub type Y = i32;
 
-        if coverage < 0 {
-            return None;
-        };
+// the coverage of the sensors far apart from their beacons does not fit into the coordinates,
+// so it is kept wider
+struct Rhombus {
+    center: Point2<i64>,
+    coverage: i64,
+}
 
-        match x_restriction {
-            Some(restriction) => Some((
-                self.calculate_boundary_with_restriction(
-                    self.center.0 - coverage,
-                    restriction.0,
-                    RestrictionKind::GreaterEq,
-                ),
-                self.calculate_boundary_with_restriction(
-                    self.center.0 + coverage,
-                    restriction.1,
-                    RestrictionKind::LessEq,
-                ),
-            )),
-            None => Some((self.center.0 - coverage, self.center.0 + coverage)),
+impl Rhombus {
+    fn new(sensor: Coordinate, beacon: Coordinate) -> Self {
+        let wide = |coordinate: Coordinate| Point2::new(coordinate.x.into(), coordinate.y.into());
+        Self {
+            center: wide(sensor),
+            coverage: wide(sensor).manhattan(wide(beacon)),
         }
     }
-}
 
-enum RestrictionKind {
-    GreaterEq,
-    LessEq,
+    fn get_x_coordinates_for_y(&self, y: Y) -> Option<Interval<i64>> {
+        let coverage = self.coverage - (self.center.y - i64::from(y)).abs();
+        (coverage >= 0).then(|| Interval::new(self.center.x - coverage, self.center.x + coverage))
+    }
 }
 
-struct Area {
+#[derive(Default)]
+pub struct Area {
     sensors: Vec<Coordinate>,
     beacons: Vec<Coordinate>,
     rhombuses: Vec<Rhombus>,
 }
 
 impl Area {
-    fn new() -> Self {
-        Self {
-            sensors: vec![],
-            beacons: vec![],
-            rhombuses: vec![],
-        }
-    }
-
-    fn add_sensor_and_beacon(&mut self, sensor: Coordinate, beacon: Coordinate) {
+    pub fn add_sensor_and_beacon(&mut self, sensor: Coordinate, beacon: Coordinate) {
         self.sensors.push(sensor);
         self.beacons.push(beacon);
         self.rhombuses.push(Rhombus::new(sensor, beacon))
     }
 
-    fn tuning_frequency(&self) -> i64 {
-        let (x_restriction, y_restriction) = self.get_restricted_area();
-
-        for line_no in y_restriction.0..=y_restriction.1 {
-            let mut lines: Vec<Coordinate> = vec![];
-            for rhombus in self.rhombuses.iter() {
-                match rhombus.get_x_coordinates_for_y(line_no, Some(x_restriction)) {
-                    Some(line) => lines.push(line),
-                    None => continue,
-                }
-            }
-            lines.sort_by(|a, b| a.0.cmp(&b.0));
-            let mut coord = *lines.get(0).unwrap();
-            for l in lines {
-                match coord.merge(l) {
-                    Ok(()) => {}
-                    Err(()) => return (l.0 - 1) as i64 * 4000000 + line_no as i64,
-                }
+    pub fn tuning_frequency(&self, bound: i32, multiplier: i64) -> Result<i64, SolveError> {
+        let (x_restriction, y_restriction) = self.get_restricted_area(bound);
+        let x_restriction = Interval::new(x_restriction.start.into(), x_restriction.end.into());
+
+        for line_no in y_restriction.start..=y_restriction.end {
+            let covered: IntervalSet<i64> = self
+                .rhombuses
+                .iter()
+                .filter_map(|rhombus| {
+                    rhombus
+                        .get_x_coordinates_for_y(line_no)?
+                        .intersection(&x_restriction)
+                })
+                .collect();
+            // the distress beacon is the only position left uncovered
+            let uncovered = covered.gaps().next();
+            if let Some(gap) = uncovered {
+                return Ok(gap.start * multiplier + i64::from(line_no));
             }
         }
-        unreachable!()
+        Err(SolveError::NoSolution(String::from(
+            "No distress beacon position found",
+        )))
     }
 
-    fn positions_not_containing_beacon(&self, y: Y) -> i64 {
-        let x_coordinates: Vec<i32> = self
+    pub fn positions_not_containing_beacon(&self, y: Y) -> i64 {
+        let covered: IntervalSet<i64> = self
             .rhombuses
             .iter()
-            .flat_map(|r| match r.get_x_coordinates_for_y(y, None) {
-                Some(line) => vec![line.0, line.1],
-                None => vec![],
-            })
-            .sorted()
+            .filter_map(|r| r.get_x_coordinates_for_y(y))
             .collect();
 
-        (*x_coordinates.get(0).unwrap()..*x_coordinates.get(x_coordinates.len() - 1).unwrap()).len()
-            as i64
+        let beacons = self
+            .beacons
+            .iter()
+            .filter(|beacon| beacon.y == y)
+            .unique()
+            .filter(|beacon| covered.contains(beacon.x.into()))
+            .count();
+        // the sensors and the beacons are i32, so the row they cover is far narrower than i64
+        covered.len().expect("the covered cells fit into i64") - beacons as i64
     }
 
-    fn get_restricted_area(&self) -> (Coordinate, Coordinate) {
+    // the ranges of the x and the y coordinates of the sensors within the bound
+    fn get_restricted_area(&self, bound: i32) -> (Interval<X>, Interval<Y>) {
         let (mut starting_x, mut ending_x) = (i32::MAX, i32::MIN);
         let (mut starting_y, mut ending_y) = (i32::MAX, i32::MIN);
 
         for sensor in self.sensors.iter() {
-            if sensor.0 >= 0 && sensor.0 < starting_x {
-                starting_x = sensor.0
+            if sensor.x >= 0 && sensor.x < starting_x {
+                starting_x = sensor.x
             }
-            if sensor.0 <= 4000000 && sensor.0 > ending_x {
-                ending_x = sensor.0
+            if sensor.x <= bound && sensor.x > ending_x {
+                ending_x = sensor.x
             }
-            if sensor.1 >= 0 && sensor.1 < starting_y {
-                starting_y = sensor.1
+            if sensor.y >= 0 && sensor.y < starting_y {
+                starting_y = sensor.y
             }
-            if sensor.1 <= 4000000 && sensor.1 > ending_y {
-                ending_y = sensor.1
+            if sensor.y <= bound && sensor.y > ending_y {
+                ending_y = sensor.y
             }
         }
 
-        ((starting_x, ending_x), (starting_y, ending_y))
+        (
+            Interval::new(starting_x, ending_x),
+            Interval::new(starting_y, ending_y),
+        )
     }
 }
 
 impl FifteenthPuzzle {
-    pub fn new() -> Self {
+    pub fn new(puzzle: PuzzleInfo) -> Self {
         Self {
-            puzzle: PuzzleInfo::new(
-                "Fifteenth Puzzle - Beacon Exclusion Zone",
-                "./inputs/15.txt",
-            ),
+            puzzle,
+            params: FifteenthParameters::default(),
         }
     }
 
-    fn read_sensors(&self) -> Area {
-        let re_coordinates = Regex::new(r".*x=(-?\d+), y=(-?\d+).*x=(-?\d+), y=(-?\d+)").unwrap();
-        let mut area = Area::new();
-        self.puzzle.input.lines().for_each(|line| {
-            let captures = re_coordinates.captures(line.trim()).unwrap();
+    pub fn read_sensors(&self) -> Result<Area, ParseError> {
+        let re_coordinates = Regex::new(
+            r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$",
+        )
+        .unwrap();
+        let mut area = Area::default();
+        for line in self.puzzle.lines().filter(|line| !line.text.is_empty()) {
+            let captures = re_coordinates.captures(line.text).ok_or_else(|| {
+                line.error(0, "`Sensor at x=N, y=N: closest beacon is at x=N, y=N`")
+            })?;
+            let mut values = [0; 4];
+            for (value, idx) in values.iter_mut().zip(1..) {
+                let capture = captures.get(idx).unwrap();
+                *value = line.parse(capture.start(), capture.as_str(), "a coordinate")?;
+            }
             area.add_sensor_and_beacon(
-                (captures[1].parse().unwrap(), captures[2].parse().unwrap()),
-                (captures[3].parse().unwrap(), captures[4].parse().unwrap()),
+                Point2::new(values[0], values[1]),
+                Point2::new(values[2], values[3]),
             );
-        });
+        }
+
+        if area.sensors.is_empty() {
+            return Err(self.puzzle.end_of_input_error("a sensor"));
+        }
+        Ok(area)
+    }
+}
+
+// sensors covering the whole searched area except for the single distress beacon, the area
+// and the row of the first part are scaled with the size
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let bound = 200 * size.max(1) as i32;
+    let distress = Point2::new(rng.range(1..=bound - 1), rng.range(1..=bound - 1));
+
+    // the sensors around the distress beacon cover everything close to it,
+    // and none of the sensors reaches it
+    let mut sensors: Vec<(Coordinate, i32)> = Vec::new();
+    let reach = [
+        distress.x,
+        bound - distress.x,
+        distress.y,
+        bound - distress.y,
+    ];
+    let offset = rng.range(1..=*reach.iter().min().unwrap());
+    for direction in [
+        Direction::UpLeft,
+        Direction::DownLeft,
+        Direction::UpRight,
+        Direction::DownRight,
+    ] {
+        let sensor = distress + direction.offset() * offset;
+        sensors.push((sensor, 2 * offset - 1));
+    }
+    for _ in 0..size {
+        let sensor = Point2::new(rng.range(0..=bound), rng.range(0..=bound));
+        if sensor.manhattan(distress) > 1 {
+            sensors.push((sensor, rng.range(1..=sensor.manhattan(distress) - 1)));
+        }
+    }
+    // every uncovered position becomes a sensor reaching as close to the distress beacon
+    // as possible
+    for y in 0..=bound {
+        while let Some(x) = uncovered_x(&sensors, y, bound, distress) {
+            let sensor = Point2::new(x, y);
+            sensors.push((sensor, sensor.manhattan(distress) - 1));
+        }
+    }
 
-        area
+    rng.shuffle(&mut sensors);
+    let lines: Vec<String> = sensors
+        .into_iter()
+        .map(|(sensor, coverage)| {
+            let x = rng.range(-coverage..=coverage);
+            let y = (coverage - x.abs()) * *rng.choose(&[-1, 1]);
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                sensor.x,
+                sensor.y,
+                sensor.x + x,
+                sensor.y + y
+            )
+        })
+        .collect();
+    Generated {
+        input: lines.join("\n"),
+        params: vec![
+            ("row", rng.range(0..=bound).to_string()),
+            ("bound", bound.to_string()),
+        ],
     }
 }
 
+fn uncovered_x(sensors: &[(Coordinate, i32)], y: Y, bound: i32, distress: Coordinate) -> Option<X> {
+    let row = IntervalSet::from(Interval::new(0, bound));
+    let mut covered: IntervalSet<X> = sensors
+        .iter()
+        .filter_map(|(sensor, coverage)| {
+            let coverage = coverage - (sensor.y - y).abs();
+            (coverage >= 0).then(|| Interval::new(sensor.x - coverage, sensor.x + coverage))
+        })
+        .collect();
+    // the distress beacon is the only gap allowed
+    if distress.y == y {
+        covered.insert(Interval::new(distress.x, distress.x));
+    }
+    row.difference(&covered)
+        .iter()
+        .next()
+        .map(|uncovered| uncovered.start)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::PuzzleInfo;
+    use crate::{property, PuzzleInfo};
 
     use super::*;
 
+    // the reference works with plain pairs of the x and the y coordinates
+    type Position = (X, Y);
+
     #[test]
-    fn tuning_frequency() {
-        assert_eq!(
-            56000011,
-            FifteenthPuzzle {
-                puzzle: get_puzzle_info(),
+    fn matches_reference() {
+        property::check(&REGISTRATION, 10, 1, &|generated, puzzle| {
+            let param = |name: &str| {
+                let (_, value) = generated
+                    .params
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .unwrap();
+                value.parse::<i32>().unwrap()
+            };
+            let sensors = reference_sensors(&generated.input);
+            let (row, bound) = (param("row"), param("bound"));
+            property::compare(puzzle, Part::First, reference_row(&sensors, row))?;
+            // the second part is only defined for a single uncovered position
+            match reference_distress_beacon(&sensors, bound)[..] {
+                [(x, y)] => property::compare(puzzle, Part::Second, x as i64 * 4000000 + y as i64),
+                _ => Ok(()),
             }
-            .read_sensors()
-            .tuning_frequency()
-        );
+        });
+    }
+
+    #[test]
+    fn coverage_drawn_every_sensor() {
+        let mut puzzle = FifteenthPuzzle::new(get_puzzle_info());
+        puzzle.params.row = 10;
+        puzzle.params.bound = 20;
+        let area = puzzle.read_sensors().unwrap();
+        let mut frames = Vec::new();
+        puzzle.frames(&area, Part::First, &mut |frame| frames.push(frame.clone()));
+        assert_eq!(15, frames.len());
+        let row = frames[14].to_string().lines().nth(10).map(String::from);
+        assert_eq!(Some("====B======================."), row.as_deref());
+
+        frames.clear();
+        puzzle.frames(&area, Part::Second, &mut |frame| frames.push(frame.clone()));
+        assert_eq!(15, frames.len());
+        assert_eq!('.', frames[13].get(16, 11).glyph);
+        assert_eq!('X', frames[14].get(16, 11).glyph);
     }
 
     #[test]
-    fn positions_not_containing_beacon() {
+    fn sensor_far_from_beacon() {
+        let input = "Sensor at x=2000000000, y=0: closest beacon is at x=-2000000000, y=0";
+        let mut puzzle = REGISTRATION.from_input(input);
+        crate::params::set_parameter(puzzle.as_mut(), "row", "0").unwrap();
         assert_eq!(
-            26,
-            FifteenthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .read_sensors()
-            .positions_not_containing_beacon(10)
+            "8000000000",
+            puzzle.solution(Part::First).unwrap().to_string()
         );
     }
 
-    fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-                Sensor at x=9, y=16: closest beacon is at x=10, y=16
-                Sensor at x=13, y=2: closest beacon is at x=15, y=3
-                Sensor at x=12, y=14: closest beacon is at x=10, y=16
-                Sensor at x=10, y=20: closest beacon is at x=10, y=16
-                Sensor at x=14, y=17: closest beacon is at x=10, y=16
-                Sensor at x=8, y=7: closest beacon is at x=2, y=10
-                Sensor at x=2, y=0: closest beacon is at x=2, y=10
-                Sensor at x=0, y=11: closest beacon is at x=2, y=10
-                Sensor at x=20, y=14: closest beacon is at x=25, y=17
-                Sensor at x=17, y=20: closest beacon is at x=21, y=22
-                Sensor at x=16, y=7: closest beacon is at x=15, y=3
-                Sensor at x=14, y=3: closest beacon is at x=15, y=3
-                Sensor at x=20, y=1: closest beacon is at x=15, y=3",
-            ),
+    #[test]
+    fn distress_beacon_not_found() {
+        for input in [
+            "Sensor at x=0, y=0: closest beacon is at x=1, y=0",
+            "Sensor at x=-5, y=-5: closest beacon is at x=-4, y=-5",
+        ] {
+            let puzzle = REGISTRATION.from_input(input);
+            assert_eq!(
+                Err(SolveError::NoSolution(String::from(
+                    "No distress beacon position found"
+                ))),
+                puzzle.solution(Part::Second)
+            );
         }
     }
+
+    #[test]
+    fn coordinate_out_of_range() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("x=16, y=7", "x=16, y=7000000000");
+        let error = FifteenthPuzzle::new(puzzle).read_sensors().err().unwrap();
+        assert_eq!((12, 19), (error.line, error.column));
+        assert_eq!("a coordinate", error.expected);
+    }
+
+    // the covered cells are counted rather than the span from the first to the last of them,
+    // the beacons within them are left out
+    #[test]
+    fn row_with_gap() {
+        let input = "Sensor at x=0, y=0: closest beacon is at x=2, y=0
+Sensor at x=10, y=0: closest beacon is at x=11, y=0";
+        let mut puzzle = REGISTRATION.from_input(input);
+        crate::params::set_parameter(puzzle.as_mut(), "row", "0").unwrap();
+        assert_eq!("6", puzzle.solution(Part::First).unwrap().to_string());
+    }
+
+    // the sensors with the beacons they are closest to
+    fn reference_sensors(input: &str) -> Vec<(Position, Position)> {
+        let re = Regex::new(r"-?\d+").unwrap();
+        input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let values: Vec<i32> = re
+                    .find_iter(line)
+                    .map(|value| value.as_str().parse().unwrap())
+                    .collect();
+                ((values[0], values[1]), (values[2], values[3]))
+            })
+            .collect()
+    }
+
+    fn distance(first: Position, second: Position) -> i32 {
+        (first.0 - second.0).abs() + (first.1 - second.1).abs()
+    }
+
+    fn is_covered(sensors: &[(Position, Position)], position: Position) -> bool {
+        sensors
+            .iter()
+            .any(|(sensor, beacon)| distance(*sensor, position) <= distance(*sensor, *beacon))
+    }
+
+    // every cell of the row reachable by a sensor is checked one by one
+    fn reference_row(sensors: &[(Position, Position)], row: Y) -> usize {
+        let reach = sensors
+            .iter()
+            .map(|(sensor, beacon)| distance(*sensor, *beacon))
+            .max()
+            .unwrap();
+        let min_x = sensors.iter().map(|(sensor, _)| sensor.0).min().unwrap() - reach;
+        let max_x = sensors.iter().map(|(sensor, _)| sensor.0).max().unwrap() + reach;
+        (min_x..=max_x)
+            .filter(|x| is_covered(sensors, (*x, row)))
+            .filter(|x| !sensors.iter().any(|(_, beacon)| *beacon == (*x, row)))
+            .count()
+    }
+
+    fn reference_distress_beacon(sensors: &[(Position, Position)], bound: i32) -> Vec<Position> {
+        (0..=bound)
+            .flat_map(|x| (0..=bound).map(move |y| (x, y)))
+            .filter(|position| !is_covered(sensors, *position))
+            .collect()
+    }
+
+    fn get_puzzle_info() -> PuzzleInfo {
+        PuzzleInfo::new(15, "Test", EXAMPLES[0].input)
+    }
 }
@@ -1,35 +1,75 @@
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, Answer, Example, Generated, Part, Puzzle, PuzzleInfo, Rng, SolveError,
+};
 
 // for more details check the https://adventofcode.com/2022/day/2
 pub struct SecondPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for SecondPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            &self.total_score(&self.first_decode_strategy()),
-            &self.total_score(&self.second_decode_strategy()),
-        );
+register!(
+    2,
+    "Second Puzzle - Rock Paper Scissors",
+    "2.txt",
+    SecondPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "A Y
+B X
+C Z",
+    answers: [Some("15"), Some("12")],
+    params: &[],
+}];
+
+impl Puzzle for SecondPuzzle {
+    // the rounds are decoded differently by the parts
+    type Model = Vec<EncodedRound>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Vec<EncodedRound>, ParseError> {
+        self.get_rounds()
+    }
+
+    fn summary(&self, rounds: &Vec<EncodedRound>) -> String {
+        format!("{} rounds", rounds.len())
+    }
+
+    fn solve(&self, rounds: &Vec<EncodedRound>, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(self
+                .total_score(rounds, self.first_decode_strategy())
+                .into()),
+            Part::Second => Ok(self
+                .total_score(rounds, self.second_decode_strategy())
+                .into()),
+        }
     }
 }
 
+// the play of the opponent and the second column of the strategy guide
+type EncodedRound = (char, char);
+
 struct Game {
     opponent_play: GameMoveVariant,
     my_play: GameMoveVariant,
 }
 #[derive(PartialEq, Clone)]
 enum GameMoveVariant {
-    ROCK,
-    PAPER,
-    SCISSORS,
+    Rock,
+    Paper,
+    Scissors,
 }
 
 enum GameResult {
-    WIN,
-    DRAW,
-    LOSE,
+    Win,
+    Draw,
+    Lose,
 }
 
 impl Game {
@@ -45,44 +85,44 @@ impl Game {
         desired_outcome: GameResult,
     ) -> GameMoveVariant {
         match desired_outcome {
-            GameResult::WIN => match first_player_move {
-                GameMoveVariant::ROCK => GameMoveVariant::PAPER,
-                GameMoveVariant::PAPER => GameMoveVariant::SCISSORS,
-                GameMoveVariant::SCISSORS => GameMoveVariant::ROCK,
+            GameResult::Win => match first_player_move {
+                GameMoveVariant::Rock => GameMoveVariant::Paper,
+                GameMoveVariant::Paper => GameMoveVariant::Scissors,
+                GameMoveVariant::Scissors => GameMoveVariant::Rock,
             },
-            GameResult::LOSE => match first_player_move {
-                GameMoveVariant::ROCK => GameMoveVariant::SCISSORS,
-                GameMoveVariant::PAPER => GameMoveVariant::ROCK,
-                GameMoveVariant::SCISSORS => GameMoveVariant::PAPER,
+            GameResult::Lose => match first_player_move {
+                GameMoveVariant::Rock => GameMoveVariant::Scissors,
+                GameMoveVariant::Paper => GameMoveVariant::Rock,
+                GameMoveVariant::Scissors => GameMoveVariant::Paper,
             },
-            GameResult::DRAW => first_player_move.clone(),
+            GameResult::Draw => first_player_move.clone(),
         }
     }
 
     fn evaluate_result(&self) -> GameResult {
         if self.opponent_play == self.my_play {
-            GameResult::DRAW
+            GameResult::Draw
         } else {
             match self.opponent_play {
-                GameMoveVariant::ROCK => {
-                    if self.my_play == GameMoveVariant::PAPER {
-                        GameResult::WIN
+                GameMoveVariant::Rock => {
+                    if self.my_play == GameMoveVariant::Paper {
+                        GameResult::Win
                     } else {
-                        GameResult::LOSE
+                        GameResult::Lose
                     }
                 }
-                GameMoveVariant::PAPER => {
-                    if self.my_play == GameMoveVariant::SCISSORS {
-                        GameResult::WIN
+                GameMoveVariant::Paper => {
+                    if self.my_play == GameMoveVariant::Scissors {
+                        GameResult::Win
                     } else {
-                        GameResult::LOSE
+                        GameResult::Lose
                     }
                 }
-                GameMoveVariant::SCISSORS => {
-                    if self.my_play == GameMoveVariant::ROCK {
-                        GameResult::WIN
+                GameMoveVariant::Scissors => {
+                    if self.my_play == GameMoveVariant::Rock {
+                        GameResult::Win
                     } else {
-                        GameResult::LOSE
+                        GameResult::Lose
                     }
                 }
             }
@@ -91,19 +131,21 @@ impl Game {
 }
 
 impl SecondPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new("Second Puzzle - Rock Paper Scissors", "./inputs/2.txt"),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
 
     fn total_score<F: Fn(char, char) -> (GameMoveVariant, GameMoveVariant)>(
         &self,
+        rounds: &[EncodedRound],
         decode_strategy: F,
     ) -> i32 {
-        self.get_games(decode_strategy)
+        rounds
             .iter()
-            .map(|game| self.score_per_game(game))
+            .map(|(opponent_play_enc, my_play_enc)| {
+                let (opponent_play, my_play) = decode_strategy(*opponent_play_enc, *my_play_enc);
+                self.score_per_game(&Game::new(opponent_play, my_play))
+            })
             .sum()
     }
 
@@ -112,15 +154,15 @@ impl SecondPuzzle {
     ) -> Box<dyn Fn(char, char) -> (GameMoveVariant, GameMoveVariant)> {
         Box::new(|opponent_play_enc, my_play_enc| {
             let opponent_play = match opponent_play_enc {
-                'A' => GameMoveVariant::ROCK,
-                'B' => GameMoveVariant::PAPER,
-                'C' => GameMoveVariant::SCISSORS,
+                'A' => GameMoveVariant::Rock,
+                'B' => GameMoveVariant::Paper,
+                'C' => GameMoveVariant::Scissors,
                 _ => panic!("Cannot decrypt the game variant"),
             };
             let my_play = match my_play_enc {
-                'X' => GameMoveVariant::ROCK,
-                'Y' => GameMoveVariant::PAPER,
-                'Z' => GameMoveVariant::SCISSORS,
+                'X' => GameMoveVariant::Rock,
+                'Y' => GameMoveVariant::Paper,
+                'Z' => GameMoveVariant::Scissors,
                 _ => panic!("Cannot decrypt the game variant"),
             };
 
@@ -133,15 +175,15 @@ impl SecondPuzzle {
     ) -> Box<dyn Fn(char, char) -> (GameMoveVariant, GameMoveVariant)> {
         Box::new(|opponent_play_enc, my_play_enc| {
             let opponent_play = match opponent_play_enc {
-                'A' => GameMoveVariant::ROCK,
-                'B' => GameMoveVariant::PAPER,
-                'C' => GameMoveVariant::SCISSORS,
+                'A' => GameMoveVariant::Rock,
+                'B' => GameMoveVariant::Paper,
+                'C' => GameMoveVariant::Scissors,
                 _ => panic!("Cannot decrypt the game variant"),
             };
             let my_play = match my_play_enc {
-                'X' => Game::get_desired_move_variant(&opponent_play, GameResult::LOSE),
-                'Z' => Game::get_desired_move_variant(&opponent_play, GameResult::WIN),
-                'Y' => Game::get_desired_move_variant(&opponent_play, GameResult::DRAW),
+                'X' => Game::get_desired_move_variant(&opponent_play, GameResult::Lose),
+                'Z' => Game::get_desired_move_variant(&opponent_play, GameResult::Win),
+                'Y' => Game::get_desired_move_variant(&opponent_play, GameResult::Draw),
 
                 _ => panic!("Cannot decrypt the game variant"),
             };
@@ -152,68 +194,73 @@ impl SecondPuzzle {
 
     fn score_per_game(&self, game: &Game) -> i32 {
         let points_per_shape = match game.my_play {
-            GameMoveVariant::ROCK => 1,
-            GameMoveVariant::PAPER => 2,
-            GameMoveVariant::SCISSORS => 3,
+            GameMoveVariant::Rock => 1,
+            GameMoveVariant::Paper => 2,
+            GameMoveVariant::Scissors => 3,
         };
         let points_per_result = match game.evaluate_result() {
-            GameResult::LOSE => 0,
-            GameResult::DRAW => 3,
-            GameResult::WIN => 6,
+            GameResult::Lose => 0,
+            GameResult::Draw => 3,
+            GameResult::Win => 6,
         };
         points_per_shape + points_per_result
     }
 
-    fn get_games<F: Fn(char, char) -> (GameMoveVariant, GameMoveVariant)>(
-        &self,
-        decode: F,
-    ) -> Vec<Game> {
+    fn get_rounds(&self) -> Result<Vec<EncodedRound>, ParseError> {
         self.puzzle
-            .input
             .lines()
+            .filter(|line| !line.text.is_empty())
             .map(|line| {
-                let line_without_spaces = line
-                    .chars()
-                    .filter(|c| !c.is_whitespace())
-                    .collect::<String>();
-                let mut moves = line_without_spaces.chars();
-                let opponent_play_enc = moves.next().expect("Opponent play does not exist");
-                let my_play_enc = moves.next().expect("My play does not exist");
-                let (opponent_play, my_play) = decode(opponent_play_enc, my_play_enc);
-                Game::new(opponent_play, my_play)
+                let mut moves = line.text.char_indices().filter(|(_, c)| !c.is_whitespace());
+                let opponent_play_enc = match moves.next() {
+                    Some((_, c @ 'A'..='C')) => c,
+                    Some((idx, _)) => return Err(line.error(idx, "opponent play A, B or C")),
+                    None => return Err(line.error(0, "opponent play A, B or C")),
+                };
+                let my_play_enc = match moves.next() {
+                    Some((_, c @ 'X'..='Z')) => c,
+                    Some((idx, _)) => return Err(line.error(idx, "my play X, Y or Z")),
+                    None => return Err(line.error(line.text.len(), "my play X, Y or Z")),
+                };
+                if let Some((idx, _)) = moves.next() {
+                    return Err(line.error(idx, "end of line"));
+                }
+                Ok((opponent_play_enc, my_play_enc))
             })
             .collect()
     }
 }
 
+// strategy guide of the rounds
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let rounds: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.choose(&['A', 'B', 'C']),
+                rng.choose(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect();
+    rounds.join("\n").into()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use crate::PuzzleInfo;
 
-    #[test]
-    fn total_score_first_strategy() {
-        let puzzle = SecondPuzzle {
-            puzzle: get_puzzle_info(),
-        };
-        assert_eq!(15, puzzle.total_score(puzzle.first_decode_strategy()))
-    }
+    use super::*;
 
     #[test]
-    fn total_score_second_strategy() {
-        let puzzle = SecondPuzzle {
-            puzzle: get_puzzle_info(),
-        };
-        assert_eq!(12, puzzle.total_score(puzzle.second_decode_strategy()))
+    fn invalid_play() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("B X", "B W");
+        let error = SecondPuzzle { puzzle }.get_rounds().unwrap_err();
+        assert_eq!((2, 3), (error.line, error.column));
+        assert_eq!("my play X, Y or Z", error.expected);
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "A Y
-                B X
-                C Z",
-            ),
-        }
+        PuzzleInfo::new(2, "Test", EXAMPLES[0].input)
     }
 }
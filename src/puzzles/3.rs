@@ -1,25 +1,60 @@
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, Answer, Example, Generated, Part, Puzzle, PuzzleInfo, Rng, SolveError,
+};
 
 // for more details check the https://adventofcode.com/2022/day/3
 pub struct ThirdPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for ThirdPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            &self.total_priority(),
-            &self.priority_of_group_badges(),
-        );
+register!(
+    3,
+    "Third Puzzle - Rucksack Reorganization",
+    "3.txt",
+    ThirdPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw",
+    answers: [Some("157"), Some("70")],
+    params: &[],
+}];
+
+impl Puzzle for ThirdPuzzle {
+    type Model = Vec<Rucksack>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Vec<Rucksack>, ParseError> {
+        self.get_rucksacks()
+    }
+
+    fn summary(&self, rucksacks: &Vec<Rucksack>) -> String {
+        format!("{} rucksacks", rucksacks.len())
+    }
+
+    fn solve(&self, rucksacks: &Vec<Rucksack>, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(self.total_priority(rucksacks).into()),
+            Part::Second => Ok(self.priority_of_group_badges(rucksacks)?.into()),
+        }
     }
 }
 
-struct Rucksack<'a> {
-    tools: &'a str,
+pub struct Rucksack {
+    tools: String,
 }
 
-impl<'a> Rucksack<'a> {
+impl Rucksack {
     fn duplicates_within_compartments(&self) -> Vec<char> {
         let left = &self.tools[..self.tools.len() / 2];
         let right = &self.tools[self.tools.len() / 2..];
@@ -29,7 +64,7 @@ impl<'a> Rucksack<'a> {
         duplicates
     }
 
-    fn common_tool_within_rucksacks(rucksacks: Vec<Self>) -> Option<char> {
+    fn common_tool_within_rucksacks(rucksacks: &[Self]) -> Option<char> {
         let mut rucksacks_iter = rucksacks.iter();
         let first_rucksack = rucksacks_iter.next().expect("there must be rucksack");
 
@@ -37,16 +72,11 @@ impl<'a> Rucksack<'a> {
             let mut is_common = true;
             let mut other_rucksacks_iter = rucksacks_iter.clone();
 
-            loop {
-                match other_rucksacks_iter.next() {
-                    Some(r) => {
-                        is_common = r.tools.contains([tool]);
-                        if !is_common {
-                            break;
-                        }
-                    }
-                    None => break,
-                };
+            for r in other_rucksacks_iter.by_ref() {
+                is_common = r.tools.contains([tool]);
+                if !is_common {
+                    break;
+                }
             }
             if is_common {
                 return Some(tool);
@@ -58,14 +88,12 @@ impl<'a> Rucksack<'a> {
 }
 
 impl ThirdPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new("Third Puzzle - Rucksack Reorganization", "./inputs/3.txt"),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
 
-    fn total_priority(&self) -> i32 {
-        self.get_rucksacks()
+    fn total_priority(&self, rucksacks: &[Rucksack]) -> i32 {
+        rucksacks
             .iter()
             .map(|r| {
                 r.duplicates_within_compartments()
@@ -76,18 +104,21 @@ impl ThirdPuzzle {
             .sum()
     }
 
-    fn priority_of_group_badges(&self) -> i32 {
-        let mut rucksacks = self.get_rucksacks().into_iter().peekable();
+    fn priority_of_group_badges(&self, rucksacks: &[Rucksack]) -> Result<i32, SolveError> {
         let mut total_priority = 0;
 
-        while rucksacks.peek().is_some() {
-            let chunk: Vec<Rucksack> = rucksacks.by_ref().take(3).collect();
-            let common_tool =
-                Rucksack::common_tool_within_rucksacks(chunk).expect("There must be common item");
+        for (idx, chunk) in rucksacks.chunks(3).enumerate() {
+            let common_tool = Rucksack::common_tool_within_rucksacks(chunk).ok_or_else(|| {
+                SolveError::NoSolution(format!(
+                    "The rucksacks {}-{} of the group have no common item",
+                    idx * 3 + 1,
+                    idx * 3 + 3
+                ))
+            })?;
             total_priority += self.priority_per_tool(common_tool);
         }
 
-        total_priority
+        Ok(total_priority)
     }
 
     fn priority_per_tool(&self, tool_as_char: char) -> i32 {
@@ -98,52 +129,91 @@ impl ThirdPuzzle {
         }
     }
 
-    fn get_rucksacks(&self) -> Vec<Rucksack> {
-        self.puzzle
-            .input
+    fn get_rucksacks(&self) -> Result<Vec<Rucksack>, ParseError> {
+        let rucksacks: Vec<Rucksack> = self
+            .puzzle
             .lines()
-            .map(|line| Rucksack { tools: line.trim() })
-            .collect()
+            .filter(|line| !line.text.is_empty())
+            .map(|line| {
+                if let Some((idx, _)) = line
+                    .text
+                    .char_indices()
+                    .find(|(_, c)| !c.is_ascii_alphabetic())
+                {
+                    return Err(line.error(idx, "an item type a-z or A-Z"));
+                }
+                if !line.text.len().is_multiple_of(2) {
+                    return Err(line.error(line.text.len(), "an even number of items"));
+                }
+                Ok(Rucksack {
+                    tools: String::from(line.text),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        // the elves are grouped by three
+        if !rucksacks.len().is_multiple_of(3) {
+            return Err(self.puzzle.end_of_input_error("a group of three rucksacks"));
+        }
+        Ok(rucksacks)
     }
 }
 
+// rucksacks in groups of three, every group carries its badge
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut rucksacks = Vec::new();
+    for _ in 0..size.div_ceil(3).max(1) {
+        let badge = *rng.choose(&items);
+        for _ in 0..3 {
+            let mut rucksack: Vec<char> = (0..rng.range(2..=16) * 2)
+                .map(|_| *rng.choose(&items))
+                .collect();
+            let idx = rng.index(rucksack.len());
+            rucksack[idx] = badge;
+            rucksacks.push(rucksack.into_iter().collect::<String>());
+        }
+    }
+    rucksacks.join("\n").into()
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::PuzzleInfo;
+
     use super::*;
 
     #[test]
-    fn total_priority() {
-        assert_eq!(
-            157,
-            ThirdPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .total_priority()
-        );
+    fn odd_number_of_items() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("ttgJtRGJQctTZtZT", "ttgJtRGJQctTZtZ");
+        let error = ThirdPuzzle { puzzle }.get_rucksacks().err().unwrap();
+        assert_eq!((5, 16), (error.line, error.column));
+        assert_eq!("an even number of items", error.expected);
+    }
+
+    #[test]
+    fn incomplete_group() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input.push_str("\nab");
+        let error = ThirdPuzzle { puzzle }.get_rucksacks().err().unwrap();
+        assert_eq!((8, 1), (error.line, error.column));
+        assert_eq!("a group of three rucksacks", error.expected);
     }
 
     #[test]
-    fn priority_of_group_badges() {
+    fn group_without_badge() {
+        let puzzle = ThirdPuzzle::new(PuzzleInfo::new(3, "Test", "ab\ncd\nef"));
+        let rucksacks = puzzle.get_rucksacks().unwrap();
         assert_eq!(
-            70,
-            ThirdPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .priority_of_group_badges()
+            Err(SolveError::NoSolution(String::from(
+                "The rucksacks 1-3 of the group have no common item"
+            ))),
+            puzzle.solve(&rucksacks, Part::Second)
         );
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "vJrwpWtwJgWrhcsFMMfFFhFp
-                jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-                PmmdzqPrVvPwwTWBwg
-                wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-                ttgJtRGJQctTZtZT
-                CrZsJsPPZsGzwwsLwLmpwMDw",
-            ),
-        }
+        PuzzleInfo::new(3, "Test", EXAMPLES[0].input)
     }
 }
@@ -1,90 +1,169 @@
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, Answer, Example, Generated, Interval, Part, Puzzle, PuzzleInfo, Rng,
+    SolveError,
+};
 
 // for more details check the https://adventofcode.com/2022/day/4
 pub struct FourthPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for FourthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            &self.fully_contained_pairs(),
-            &self.overlapping_pairs(),
-        );
+register!(
+    4,
+    "Fourth Puzzle - Camp Cleanup",
+    "4.txt",
+    FourthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8",
+    answers: [Some("2"), Some("4")],
+    params: &[],
+}];
+
+impl Puzzle for FourthPuzzle {
+    type Model = Vec<CleanupAssignment>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Vec<CleanupAssignment>, ParseError> {
+        self.get_assignments()
+    }
+
+    fn summary(&self, assignments: &Vec<CleanupAssignment>) -> String {
+        format!("{} pairs of elves", assignments.len())
+    }
+
+    fn solve(
+        &self,
+        assignments: &Vec<CleanupAssignment>,
+        part: Part,
+    ) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(self.fully_contained_pairs(assignments).into()),
+            Part::Second => Ok(self.overlapping_pairs(assignments).into()),
+        }
     }
 }
 
-struct CleanupAssignment {
-    first_unit: (i32, i32),
-    second_unit: (i32, i32),
+pub struct CleanupAssignment {
+    first_unit: Interval<i32>,
+    second_unit: Interval<i32>,
 }
 
 impl CleanupAssignment {
     fn is_fully_contained(&self) -> bool {
-        self.first_unit.0 <= self.second_unit.0 && self.first_unit.1 >= self.second_unit.1
-            || self.second_unit.0 <= self.first_unit.0 && self.second_unit.1 >= self.first_unit.1
+        self.first_unit.contains_interval(&self.second_unit)
+            || self.second_unit.contains_interval(&self.first_unit)
     }
 
     fn is_overlapping(&self) -> bool {
-        self.first_unit.0 <= self.second_unit.1 && self.second_unit.0 <= self.first_unit.1
+        self.first_unit.overlaps(&self.second_unit)
     }
 }
 
 impl FourthPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new("Fourth Puzzle - Camp Cleanup", "./inputs/4.txt"),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
 
-    fn fully_contained_pairs(&self) -> i32 {
-        self.get_assignments()
+    fn fully_contained_pairs(&self, assignments: &[CleanupAssignment]) -> i32 {
+        assignments
             .iter()
             .filter(|assignment| assignment.is_fully_contained())
             .collect::<Vec<&CleanupAssignment>>()
             .len() as i32
     }
 
-    fn overlapping_pairs(&self) -> i32 {
-        self.get_assignments()
+    fn overlapping_pairs(&self, assignments: &[CleanupAssignment]) -> i32 {
+        assignments
             .iter()
             .filter(|assignment| assignment.is_overlapping())
             .collect::<Vec<&CleanupAssignment>>()
             .len() as i32
     }
 
-    fn get_assignments(&self) -> Vec<CleanupAssignment> {
+    fn get_assignments(&self) -> Result<Vec<CleanupAssignment>, ParseError> {
         let mut assignments = Vec::new();
-        self.puzzle.input.lines().for_each(|line| {
+        // the section ids are expected to be separated exactly in this order
+        let separators = [('-', "`-`"), (',', "`,`"), ('-', "`-`")];
+
+        for line in self.puzzle.lines().filter(|line| !line.text.is_empty()) {
             let mut numbers = Vec::new();
+            let mut number_starts = Vec::new();
             let mut number_builder = String::new();
-            let chars: Vec<char> = line.chars().collect();
-            for (idx, c) in chars.iter().enumerate() {
-                if c.is_digit(10) {
-                    number_builder.push(*c);
-                    if idx == chars.len() - 1 {
-                        numbers.push(number_builder.parse::<i32>().unwrap());
+            for (idx, c) in line.text.char_indices() {
+                if c.is_ascii_digit() {
+                    number_builder.push(c);
+                    continue;
+                }
+                let number_start = idx - number_builder.len();
+                if number_builder.is_empty() {
+                    return Err(line.error(idx, "a section id"));
+                }
+                match separators.get(numbers.len()) {
+                    Some((separator, _)) if *separator == c => {
+                        numbers.push(line.parse(number_start, &number_builder, "a section id")?);
+                        number_starts.push(number_start);
+                        number_builder.clear();
                     }
-                } else {
-                    numbers.push(number_builder.parse::<i32>().unwrap());
-                    number_builder.clear();
+                    Some((_, expected)) => return Err(line.error(idx, expected)),
+                    None => return Err(line.error(idx, "end of line")),
                 }
             }
+            let number_start = line.text.len() - number_builder.len();
+            if number_builder.is_empty() {
+                return Err(line.error(number_start, "a section id"));
+            }
+            if let Some((_, expected)) = separators.get(numbers.len()) {
+                return Err(line.error(line.text.len(), expected));
+            }
+            numbers.push(line.parse(number_start, &number_builder, "a section id")?);
+            number_starts.push(number_start);
 
+            // the ends of the sections follow their starts
+            for idx in [1, 3] {
+                if numbers[idx] < numbers[idx - 1] {
+                    return Err(line.error(number_starts[idx], "a section end not below its start"));
+                }
+            }
             let [first_unit_start, first_unit_end, second_unit_start, second_unit_end] =
                 <[i32; 4]>::try_from(&numbers[..]).unwrap();
 
             assignments.push(CleanupAssignment {
-                first_unit: (first_unit_start, first_unit_end),
-                second_unit: (second_unit_start, second_unit_end),
+                first_unit: Interval::new(first_unit_start, first_unit_end),
+                second_unit: Interval::new(second_unit_start, second_unit_end),
             })
-        });
+        }
 
-        assignments
+        Ok(assignments)
     }
 }
 
+// pairs of the section assignments
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let pairs: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            let mut section = || {
+                let start = rng.range(1..=99);
+                (start, rng.range(start..=99))
+            };
+            let (first, second) = (section(), section());
+            format!("{}-{},{}-{}", first.0, first.1, second.0, second.1)
+        })
+        .collect();
+    pairs.join("\n").into()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::PuzzleInfo;
@@ -92,38 +171,26 @@ mod tests {
     use super::*;
 
     #[test]
-    fn fully_contained_pairs() {
-        assert_eq!(
-            2,
-            FourthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .fully_contained_pairs()
-        );
+    fn truncated_assignment() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("2-8,3-7", "2-8,3");
+        let error = FourthPuzzle { puzzle }.get_assignments().err().unwrap();
+        assert_eq!((4, 6), (error.line, error.column));
+        assert_eq!("`-`", error.expected);
+        assert_eq!("end of line", error.found);
     }
 
     #[test]
-    fn overlapping_pairs() {
-        assert_eq!(
-            4,
-            FourthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .overlapping_pairs()
-        );
+    fn reversed_section() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("2-8,3-7", "2-8,7-3");
+        let error = FourthPuzzle { puzzle }.get_assignments().err().unwrap();
+        assert_eq!((4, 7), (error.line, error.column));
+        assert_eq!("a section end not below its start", error.expected);
+        assert_eq!("`3`", error.found);
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "2-4,6-8
-2-3,4-5
-5-7,7-9
-2-8,3-7
-6-6,4-6
-2-6,4-8",
-            ),
-        }
+        PuzzleInfo::new(4, "Test", EXAMPLES[0].input)
     }
 }
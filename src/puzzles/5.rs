@@ -3,27 +3,67 @@ use std::collections::HashMap;
 use itertools::Itertools;
 use regex::Regex;
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, Answer, Example, Generated, Part, Puzzle, PuzzleInfo, Rng, SolveError,
+};
 
 pub struct FifthPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for FifthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            &self
-                .get_storage(Box::new(CraneOldModel {}))
-                .crates_on_top_of_stacks(),
-            &self
-                .get_storage(Box::new(CraneNewModel {}))
-                .crates_on_top_of_stacks(),
-        );
+register!(
+    5,
+    "Fifth Puzzle - Supply Stacks",
+    "5.txt",
+    FifthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2",
+    answers: [Some("CMZ"), Some("MCD")],
+    params: &[],
+}];
+
+impl Puzzle for FifthPuzzle {
+    type Model = Storage;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Storage, ParseError> {
+        self.get_storage()
+    }
+
+    fn summary(&self, storage: &Storage) -> String {
+        let crates: usize = storage.stacks.iter().map(|stack| stack.items.len()).sum();
+        format!(
+            "{} stacks, {crates} crates, {} instructions",
+            storage.stacks.len(),
+            storage.instructions.len()
+        )
+    }
+
+    fn solve(&self, storage: &Storage, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(storage.crates_on_top_of_stacks(&CraneOldModel {}).into()),
+            Part::Second => Ok(storage.crates_on_top_of_stacks(&CraneNewModel {}).into()),
+        }
     }
 }
 
-struct Stack {
+#[derive(Clone)]
+pub struct Stack {
     items: Vec<char>,
 }
 
@@ -37,7 +77,7 @@ impl Stack {
     }
 }
 
-struct Instruction {
+pub struct Instruction {
     amount: i32,
     from_stack: usize,
     to_stack: usize,
@@ -53,30 +93,25 @@ impl Instruction {
     }
 }
 
-struct Storage {
+pub struct Storage {
     stacks: Vec<Stack>,
     instructions: Vec<Instruction>,
-    crane_strategy: Box<dyn CraneStrategy>,
 }
 
 impl Storage {
-    fn new(
-        stacks: Vec<Stack>,
-        instructions: Vec<Instruction>,
-        crane_strategy: Box<dyn CraneStrategy>,
-    ) -> Self {
+    fn new(stacks: Vec<Stack>, instructions: Vec<Instruction>) -> Self {
         Self {
             stacks,
             instructions,
-            crane_strategy,
         }
     }
 
-    fn crates_on_top_of_stacks(&mut self) -> String {
-        self.crane_strategy
-            .execute_instructions(&self.instructions, &mut self.stacks);
+    // the instructions are executed on a copy of the stacks, the storage is kept as drawn
+    pub fn crates_on_top_of_stacks(&self, crane_strategy: &dyn CraneStrategy) -> String {
+        let mut stacks = self.stacks.clone();
+        crane_strategy.execute_instructions(&self.instructions, &mut stacks);
         let mut crates_on_top = String::new();
-        for stack in self.stacks.iter() {
+        for stack in stacks.iter() {
             match stack.peek() {
                 Some(c) => crates_on_top.push(*c),
                 None => continue,
@@ -86,14 +121,14 @@ impl Storage {
     }
 }
 
-trait CraneStrategy {
-    fn execute_instructions(&self, instructions: &Vec<Instruction>, stacks: &mut Vec<Stack>);
+pub trait CraneStrategy {
+    fn execute_instructions(&self, instructions: &[Instruction], stacks: &mut Vec<Stack>);
 }
 
-struct CraneOldModel {}
+pub struct CraneOldModel {}
 
 impl CraneStrategy for CraneOldModel {
-    fn execute_instructions(&self, instructions: &Vec<Instruction>, stacks: &mut Vec<Stack>) {
+    fn execute_instructions(&self, instructions: &[Instruction], stacks: &mut Vec<Stack>) {
         instructions.iter().for_each(|instruction| {
             for _ in 0..instruction.amount {
                 let cr = stacks
@@ -113,10 +148,10 @@ impl CraneStrategy for CraneOldModel {
     }
 }
 
-struct CraneNewModel {}
+pub struct CraneNewModel {}
 
 impl CraneStrategy for CraneNewModel {
-    fn execute_instructions(&self, instructions: &Vec<Instruction>, stacks: &mut Vec<Stack>) {
+    fn execute_instructions(&self, instructions: &[Instruction], stacks: &mut Vec<Stack>) {
         instructions.iter().for_each(|instruction| {
             let from_stack = stacks.get_mut(instruction.from_stack - 1).unwrap();
             let mut to_move: Vec<char> = from_stack
@@ -131,17 +166,17 @@ impl CraneStrategy for CraneNewModel {
 }
 
 impl FifthPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new("Fifth Puzzle - Supply Stacks", "./inputs/5.txt"),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
 
-    fn get_storage(&self, crane_strategy: Box<dyn CraneStrategy>) -> Storage {
-        Storage::new(self.get_stacks(), self.get_instructions(), crane_strategy)
+    pub fn get_storage(&self) -> Result<Storage, ParseError> {
+        let stacks = self.get_stacks()?;
+        let instructions = self.get_instructions(stacks.len())?;
+        Ok(Storage::new(stacks, instructions))
     }
 
-    fn get_stacks(&self) -> Vec<Stack> {
+    fn get_stacks(&self) -> Result<Vec<Stack>, ParseError> {
         let mut map_of_crates = HashMap::new();
         let re_crates = Regex::new(r".*\[[A-Z]\].*").unwrap();
         for cap in re_crates.captures_iter(&self.puzzle.input) {
@@ -169,22 +204,88 @@ impl FifthPuzzle {
             }
             stacks.push(Stack::new(crates));
         });
-        stacks
+
+        if stacks.is_empty() {
+            return Err(self
+                .puzzle
+                .end_of_input_error("a drawing of the crate stacks"));
+        }
+        Ok(stacks)
     }
 
-    fn get_instructions(&self) -> Vec<Instruction> {
-        let re_moves = Regex::new(r".*move (\d+) from (\d+) to (\d+).*").unwrap();
+    fn get_instructions(&self, stacks_count: usize) -> Result<Vec<Instruction>, ParseError> {
+        let re_crates = Regex::new(r"^\[[A-Z]\].*|^[\d ]+$").unwrap();
+        let re_moves = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
         let mut instructions = Vec::new();
 
-        for cap_move in re_moves.captures_iter(&self.puzzle.input) {
+        for line in self.puzzle.lines() {
+            if line.text.is_empty() || re_crates.is_match(line.text) {
+                continue;
+            }
+            let cap_move = re_moves
+                .captures(line.text)
+                .ok_or_else(|| line.error(0, "`move N from N to N`"))?;
+            let mut stack_ids = [0; 2];
+            for (stack_id, cap_stack) in stack_ids.iter_mut().zip([2, 3]) {
+                let cap_stack = cap_move.get(cap_stack).unwrap();
+                *stack_id = line
+                    .parse(cap_stack.start(), cap_stack.as_str(), "a stack id")
+                    .ok()
+                    .filter(|id| (1..=stacks_count).contains(id))
+                    .ok_or_else(|| {
+                        line.error(
+                            cap_stack.start(),
+                            &format!("a stack id between 1 and {stacks_count}"),
+                        )
+                    })?;
+            }
+            let amount = cap_move.get(1).unwrap();
             instructions.push(Instruction::new(
-                *&cap_move[1].parse().unwrap(),
-                *&cap_move[2].parse().unwrap(),
-                *&cap_move[3].parse().unwrap(),
+                line.parse(amount.start(), amount.as_str(), "an amount of crates")?,
+                stack_ids[0],
+                stack_ids[1],
             ));
         }
-        instructions
+        Ok(instructions)
+    }
+}
+
+// drawing of the stacks followed by the moves, every move takes only the crates available
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut heights: Vec<usize> = (0..rng.range(3..=9)).map(|_| rng.range(1..=8)).collect();
+    let mut lines = Vec::new();
+    for level in (0..*heights.iter().max().unwrap()).rev() {
+        let row: Vec<String> = heights
+            .iter()
+            .map(|height| match *height > level {
+                true => format!("[{}]", rng.range(b'A'..=b'Z') as char),
+                false => String::from("   "),
+            })
+            .collect();
+        lines.push(row.join(" ").trim_end().to_string());
+    }
+    let ids: Vec<String> = (1..=heights.len()).map(|id| format!(" {id} ")).collect();
+    lines.push(ids.join(" "));
+    lines.push(String::new());
+
+    for _ in 0..size.max(1) {
+        let from_stack = loop {
+            let idx = rng.index(heights.len());
+            if heights[idx] > 0 {
+                break idx;
+            }
+        };
+        let to_stack = (from_stack + rng.range(1..=heights.len() - 1)) % heights.len();
+        let amount = rng.range(1..=heights[from_stack]);
+        heights[from_stack] -= amount;
+        heights[to_stack] += amount;
+        lines.push(format!(
+            "move {amount} from {} to {}",
+            from_stack + 1,
+            to_stack + 1
+        ));
     }
+    lines.join("\n").into()
 }
 
 #[cfg(test)]
@@ -192,45 +293,19 @@ mod tests {
     use crate::PuzzleInfo;
 
     use super::*;
-    #[test]
-    fn crates_on_top_of_stacks_old_model() {
-        assert_eq!(
-            String::from("CMZ"),
-            FifthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .get_storage(Box::new(CraneOldModel {}))
-            .crates_on_top_of_stacks()
-        )
-    }
 
     #[test]
-    fn crates_on_top_of_stacks_new_model() {
-        assert_eq!(
-            String::from("MCD"),
-            FifthPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .get_storage(Box::new(CraneNewModel {}))
-            .crates_on_top_of_stacks()
-        )
+    fn instruction_with_unknown_stack() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle
+            .input
+            .replace("move 2 from 2 to 1", "move 2 from 4 to 1");
+        let error = FifthPuzzle { puzzle }.get_storage().err().unwrap();
+        assert_eq!((8, 13), (error.line, error.column));
+        assert_eq!("a stack id between 1 and 3", error.expected);
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "
-                    [D]    
-                [N] [C]    
-                [Z] [M] [P]
-                 1   2   3 
-                
-                move 1 from 2 to 1
-                move 3 from 1 to 3
-                move 2 from 2 to 1
-                move 1 from 1 to 2",
-            ),
-        }
+        PuzzleInfo::new(5, "Test", EXAMPLES[0].input)
     }
 }
@@ -1,46 +1,126 @@
 use std::collections::HashMap;
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, Answer, Example, Generated, Part, Puzzle, PuzzleInfo, Rng, SolveError,
+};
 
 pub struct SixthPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for SixthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.chars_before_first_marker(4),
-            self.chars_before_first_marker(14),
-        );
+register!(
+    6,
+    "Sixth Puzzle - Tuning Trouble",
+    "6.txt",
+    SixthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+        answers: [Some("7"), Some("19")],
+        params: &[],
+    },
+    Example {
+        input: "bvwbjplbgvbhsrlpgdmjqwftvncz",
+        answers: [Some("5"), Some("23")],
+        params: &[],
+    },
+    Example {
+        input: "nppdvjthqldpwncqszvftbrmjlhg",
+        answers: [Some("6"), Some("23")],
+        params: &[],
+    },
+    Example {
+        input: "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
+        answers: [Some("10"), Some("29")],
+        params: &[],
+    },
+    Example {
+        input: "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw",
+        answers: [Some("11"), Some("26")],
+        params: &[],
+    },
+];
+
+impl Puzzle for SixthPuzzle {
+    // the datastream itself
+    type Model = String;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<String, ParseError> {
+        let mut lines = self.puzzle.lines().filter(|line| !line.text.is_empty());
+        let datastream = lines
+            .next()
+            .ok_or_else(|| self.puzzle.end_of_input_error("a datastream"))?;
+        if let Some(idx) = datastream.text.find(|c: char| !c.is_ascii_lowercase()) {
+            return Err(datastream.error(idx, "a lowercase letter"));
+        }
+        if let Some(line) = lines.next() {
+            return Err(line.error(0, "end of input"));
+        }
+        Ok(String::from(datastream.text))
+    }
+
+    fn summary(&self, datastream: &String) -> String {
+        format!("{} characters", datastream.len())
+    }
+
+    fn solve(&self, datastream: &String, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(self.chars_before_first_marker(datastream, 4)?.into()),
+            Part::Second => Ok(self.chars_before_first_marker(datastream, 14)?.into()),
+        }
     }
 }
 
 impl SixthPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new("Sixth Puzzle - Tuning Trouble", "./inputs/6.txt"),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
 
-    fn chars_before_first_marker(&self, message_breakpoint: usize) -> usize {
+    fn chars_before_first_marker(
+        &self,
+        datastream: &str,
+        message_breakpoint: usize,
+    ) -> Result<usize, ParseError> {
         let mut chars_as_map: HashMap<char, usize> = HashMap::new();
 
-        for (i, c) in self.puzzle.input.chars().enumerate() {
-            if chars_as_map.len() == message_breakpoint {
-                return i;
-            }
-
+        for (i, c) in datastream.chars().enumerate() {
             if let Some(existing_value) = chars_as_map.get(&c).cloned() {
                 chars_as_map.retain(|_key, value| *value > existing_value);
             }
 
             chars_as_map.insert(c, i);
+            // the marker may end the datastream as well
+            if chars_as_map.len() == message_breakpoint {
+                return Ok(i + 1);
+            }
         }
-        panic!("Invalid input");
+        Err(self.puzzle.end_of_input_error(&format!(
+            "a marker of {message_breakpoint} different characters"
+        )))
     }
 }
 
+// datastream with both markers somewhere after a run of only a few different characters
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let letters: Vec<char> = ('a'..='z').collect();
+    let mut datastream: String = (0..rng.range(0..=size))
+        .map(|_| *rng.choose(&letters[..3]))
+        .collect();
+    let mut marker = letters.clone();
+    rng.shuffle(&mut marker);
+    datastream.extend(&marker[..14]);
+    datastream.extend((0..rng.range(0..=size)).map(|_| *rng.choose(&letters)));
+    datastream.into()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::PuzzleInfo;
@@ -48,26 +128,39 @@ mod tests {
     use super::*;
 
     #[test]
-    fn chars_before_first_marker() {
-        assert_eq!(
-            11,
-            SixthPuzzle {
-                puzzle: get_puzzle_info("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw")
-            }
-            .chars_before_first_marker(4)
-        );
+    fn missing_marker() {
+        let error = SixthPuzzle {
+            puzzle: get_puzzle_info("abcabcabc"),
+        }
+        .chars_before_first_marker("abcabcabc", 4)
+        .unwrap_err();
+        assert_eq!((2, 1), (error.line, error.column));
+        assert_eq!("a marker of 4 different characters", error.expected);
 
-        assert_eq!(
-            19,
-            SixthPuzzle {
-                puzzle: get_puzzle_info("mjqjpqmgbljsphdztnvjfqwrcgsmlb")
-            }
-            .chars_before_first_marker(14)
-        )
+        let puzzle = SixthPuzzle {
+            puzzle: get_puzzle_info("aabcd"),
+        };
+        assert_eq!(Ok(5), puzzle.chars_before_first_marker("aabcd", 4));
+    }
+
+    #[test]
+    fn invalid_datastream() {
+        let puzzle = SixthPuzzle {
+            puzzle: get_puzzle_info("abc\nabc"),
+        };
+        assert_eq!((2, 1), {
+            let error = puzzle.parse().unwrap_err();
+            (error.line, error.column)
+        });
+        let puzzle = SixthPuzzle {
+            puzzle: get_puzzle_info("abC"),
+        };
+        assert_eq!("`C`", puzzle.parse().unwrap_err().found);
     }
 
     fn get_puzzle_info(input: &str) -> PuzzleInfo {
         PuzzleInfo {
+            day: 6,
             name: String::from("Test"),
             input: String::from(input),
         }
@@ -1,28 +1,111 @@
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
     hash::{Hash, Hasher},
 };
 
 use itertools::Itertools;
 use regex::Regex;
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, Answer, Example, Generated, Parameters, Part, Puzzle, PuzzleInfo, Rng,
+    SolveError,
+};
 
 pub struct SeventhPuzzle {
     puzzle: PuzzleInfo,
+    params: SeventhParameters,
 }
 
-impl Solution for SeventhPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.total_size_of_dirs_to_delete(100000),
-            self.size_of_dir_to_delete(70000000, 30000000),
-        );
+parameters!(SeventhParameters {
+    // the directories up to this size are summed in the first part
+    size_limit: i64 = 100000,
+    disk_space: i64 = 70000000,
+    // the unused space needed for the update in the second part
+    needed_space: i64 = 30000000,
+});
+
+register!(
+    7,
+    "Seventh Puzzle - No Space Left On Device",
+    "7.txt",
+    SeventhPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k",
+    answers: [Some("95437"), Some("24933642")],
+    params: &[],
+}];
+
+impl Puzzle for SeventhPuzzle {
+    type Model = FileSystem;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<FileSystem, ParseError> {
+        self.get_file_system()
+    }
+
+    fn summary(&self, file_system: &FileSystem) -> String {
+        let files: usize = file_system
+            .directories
+            .values()
+            .map(|directory| directory.files.len())
+            .sum();
+        format!(
+            "{} directories, {files} files",
+            file_system.directories.len()
+        )
+    }
+
+    fn solve(&self, file_system: &FileSystem, part: Part) -> Result<Answer, SolveError> {
+        let dir_sizes = file_system.get_dir_sizes();
+        match part {
+            Part::First => Ok(self
+                .total_size_of_dirs_to_delete(&dir_sizes, self.params.size_limit)
+                .into()),
+            Part::Second => Ok(self
+                .size_of_dir_to_delete(
+                    &dir_sizes,
+                    self.params.disk_space,
+                    self.params.needed_space,
+                )?
+                .into()),
+        }
+    }
+
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        Some(&mut self.params)
     }
 }
 
-struct FileSystem {
+pub struct FileSystem {
     directories: HashMap<u64, Directory>,
 }
 
@@ -80,7 +163,14 @@ impl FileSystem {
         FileSystem { directories }
     }
 
-    fn calculate_dir_size(&self, dir_hash: u64) -> i32 {
+    fn get_dir_sizes(&self) -> Vec<i64> {
+        self.directories
+            .keys()
+            .map(|dir_name| self.calculate_dir_size(*dir_name))
+            .collect()
+    }
+
+    fn calculate_dir_size(&self, dir_hash: u64) -> i64 {
         let dir = self.directories.get(&dir_hash).expect("Dir does not exist");
         let mut curr_size = dir.size();
 
@@ -124,7 +214,7 @@ impl Directory {
         self.sub_dir_names.push(String::from(sub_dir_name));
     }
 
-    fn size(&self) -> i32 {
+    fn size(&self) -> i64 {
         let mut size = 0;
         self.files.iter().for_each(|f| {
             size += f.size;
@@ -135,11 +225,11 @@ impl Directory {
 }
 
 struct File {
-    size: i32,
+    size: i64,
 }
 
 impl File {
-    fn new(size: i32) -> Self {
+    fn new(size: i64) -> Self {
         Self { size }
     }
 }
@@ -155,79 +245,108 @@ enum CDKind {
 }
 
 enum LSOutput {
-    File(i32),
+    File(i64),
     Directory(String),
 }
 
 impl SeventhPuzzle {
-    pub fn new() -> Self {
+    pub fn new(puzzle: PuzzleInfo) -> Self {
         Self {
-            puzzle: PuzzleInfo::new("Seventh Puzzle - No Space Left On Device", "./inputs/7.txt"),
+            puzzle,
+            params: SeventhParameters::default(),
         }
     }
 
-    fn total_size_of_dirs_to_delete(&self, directory_size_limit: i32) -> i32 {
-        self.get_dir_sizes()
+    fn total_size_of_dirs_to_delete(&self, dir_sizes: &[i64], directory_size_limit: i64) -> i64 {
+        dir_sizes
             .iter()
             .filter(|dir_size| **dir_size < directory_size_limit)
             .sum()
     }
 
-    fn size_of_dir_to_delete(&self, total_space: i32, space_needed: i32) -> i32 {
-        let dir_sizes = self.get_dir_sizes();
+    // even the root may be too small to free up the needed space
+    fn size_of_dir_to_delete(
+        &self,
+        dir_sizes: &[i64],
+        total_space: i64,
+        space_needed: i64,
+    ) -> Result<i64, SolveError> {
         let largest_dir = dir_sizes.iter().max().unwrap();
         let available_space = total_space - largest_dir;
 
-        *dir_sizes
+        dir_sizes
             .iter()
             .sorted()
             .find(|size| (**size + available_space) > space_needed)
-            .unwrap()
+            .copied()
+            .ok_or_else(|| {
+                SolveError::NoSolution(format!(
+                    "No directory frees up enough space for the update of {space_needed}"
+                ))
+            })
     }
 
-    fn get_dir_sizes(&self) -> Vec<i32> {
-        let line_commands = self.get_line_commands();
-        let file_system = FileSystem::build_file_system_from_line_commands(line_commands);
-
-        file_system
-            .directories
-            .iter()
-            .map(|(dir_name, _)| file_system.calculate_dir_size(*dir_name))
-            .collect()
+    fn get_file_system(&self) -> Result<FileSystem, ParseError> {
+        let line_commands = self.get_line_commands()?;
+        Ok(FileSystem::build_file_system_from_line_commands(
+            line_commands,
+        ))
     }
 
-    fn get_line_commands(&self) -> Vec<LineCommand> {
-        let re_command = Regex::new(r"\$.*").unwrap();
-        let re_cd_down = Regex::new(r".*cd [a-z]").unwrap();
-        let re_cd_up = Regex::new(r".*cd ..").unwrap();
-        let re_file = Regex::new(r"(\d+) (.*)").unwrap();
-        let re_dir = Regex::new(r".* (\w+)").unwrap();
+    fn get_line_commands(&self) -> Result<Vec<LineCommand>, ParseError> {
+        let re_command = Regex::new(r"^\$ ").unwrap();
+        let re_cd_root = Regex::new(r"^\$ cd /$").unwrap();
+        let re_cd_up = Regex::new(r"^\$ cd \.\.$").unwrap();
+        let re_cd_down = Regex::new(r"^\$ cd (\S+)$").unwrap();
+        let re_ls = Regex::new(r"^\$ ls$").unwrap();
+        let re_file = Regex::new(r"^(\d+) \S+$").unwrap();
+        let re_dir = Regex::new(r"^dir (\S+)$").unwrap();
 
         let mut line_commads = Vec::new();
         let mut file_system_nodes = Vec::new();
+        // the names of the directories from the root to the current one, and the directories
+        // listed so far which can be entered
+        let mut path: Vec<String> = Vec::new();
+        let mut listed: HashSet<Vec<String>> = HashSet::new();
 
-        for line in self.puzzle.input.lines() {
-            let line = line.trim();
-
-            if re_command.is_match(line) {
+        for line in self.puzzle.lines().filter(|line| !line.text.is_empty()) {
+            if re_command.is_match(line.text) {
                 if !file_system_nodes.is_empty() {
-                    line_commads.push(LineCommand::LS(file_system_nodes.drain(..).collect()))
+                    line_commads.push(LineCommand::LS(std::mem::take(&mut file_system_nodes)))
                 }
-                if re_cd_down.is_match(line) {
-                    let dir_name = re_dir.captures(line).unwrap();
-                    line_commads.push(LineCommand::CD(CDKind::Down(String::from(&dir_name[1]))));
-                } else if re_cd_up.is_match(line) {
+                if re_cd_root.is_match(line.text) {
+                    // the root can be entered only from the outside
+                    if !path.is_empty() || !line_commads.is_empty() {
+                        return Err(line.error(5, "a directory name"));
+                    }
+                } else if re_cd_up.is_match(line.text) {
+                    if path.pop().is_none() {
+                        return Err(line.error(5, "a directory name while in the root"));
+                    }
                     line_commads.push(LineCommand::CD(CDKind::Up));
+                } else if let Some(dir_name) = re_cd_down.captures(line.text) {
+                    path.push(String::from(&dir_name[1]));
+                    if !listed.contains(&path) {
+                        return Err(line.error(5, "a directory listed in the current one"));
+                    }
+                    line_commads.push(LineCommand::CD(CDKind::Down(String::from(&dir_name[1]))));
+                } else if !re_ls.is_match(line.text) {
+                    return Err(line.error(2, "`cd` or `ls` command"));
                 }
 
                 continue;
             }
 
-            if let Some(file) = re_file.captures(line) {
-                file_system_nodes.push(LSOutput::File(*&file[1].parse().unwrap()))
-            } else {
-                let dir_name = re_dir.captures(line).unwrap();
+            if let Some(file) = re_file.captures(line.text) {
+                // a single file fits into i32, the sums of them are kept wider so they cannot
+                // overflow
+                let size: i32 = line.parse(0, &file[1], "a file size")?;
+                file_system_nodes.push(LSOutput::File(i64::from(size)))
+            } else if let Some(dir_name) = re_dir.captures(line.text) {
+                listed.insert([&path[..], &[String::from(&dir_name[1])]].concat());
                 file_system_nodes.push(LSOutput::Directory(String::from(&dir_name[1])));
+            } else {
+                return Err(line.error(0, "a command, a directory or a file"));
             }
         }
 
@@ -235,7 +354,54 @@ impl SeventhPuzzle {
             line_commads.push(LineCommand::LS(file_system_nodes))
         }
 
-        line_commads
+        Ok(line_commads)
+    }
+}
+
+// transcript of the terminal walking through the whole file system from the root
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let mut lines = vec![String::from("$ cd /")];
+    let mut directories = size.max(1);
+    // the total size must fit into the disk
+    let max_file_size = (40_000_000 / (4 * (directories + 1))).clamp(1, 300_000);
+    walk_directory(rng, &mut directories, max_file_size, 0, &mut lines);
+    lines.join("\n").into()
+}
+
+fn walk_directory(
+    rng: &mut Rng,
+    directories: &mut usize,
+    max_file_size: usize,
+    depth: usize,
+    lines: &mut Vec<String>,
+) {
+    let letters: Vec<char> = ('a'..='z').collect();
+    let name = |rng: &mut Rng| -> String {
+        (0..rng.range(1..=8))
+            .map(|_| *rng.choose(&letters))
+            .collect()
+    };
+
+    lines.push(String::from("$ ls"));
+    let sub_dirs = match depth {
+        0..=7 => rng.range(0..=3).min(*directories),
+        _ => 0,
+    };
+    *directories -= sub_dirs;
+    let sub_dir_names: Vec<String> = (0..sub_dirs)
+        .map(|idx| format!("{}{idx}", name(rng)))
+        .collect();
+    for sub_dir_name in &sub_dir_names {
+        lines.push(format!("dir {sub_dir_name}"));
+    }
+    for _ in 0..rng.range(0..=4) {
+        let file_size = rng.range(1..=max_file_size);
+        lines.push(format!("{file_size} {}.{}", name(rng), name(rng)));
+    }
+    for sub_dir_name in &sub_dir_names {
+        lines.push(format!("$ cd {sub_dir_name}"));
+        walk_directory(rng, directories, max_file_size, depth + 1, lines);
+        lines.push(String::from("$ cd .."));
     }
 }
 
@@ -246,55 +412,51 @@ mod tests {
     use super::*;
 
     #[test]
-    fn size_of_dir_to_delete() {
+    fn leaving_the_root() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replacen("$ cd a", "$ cd ..", 1);
+        let error = SeventhPuzzle::new(puzzle).get_file_system().err().unwrap();
+        assert_eq!((7, 6), (error.line, error.column));
+        assert_eq!("`..`", error.found);
+    }
+
+    #[test]
+    fn entering_unlisted_directory() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replacen("$ cd e", "$ cd x", 1);
+        let error = SeventhPuzzle::new(puzzle).get_file_system().err().unwrap();
+        assert_eq!((13, 6), (error.line, error.column));
+        assert_eq!("a directory listed in the current one", error.expected);
+    }
+
+    #[test]
+    fn sizes_beyond_i32() {
+        let input = "$ cd /\n$ ls\n2147483647 a\n2147483647 b";
+        let mut puzzle = SeventhPuzzle::new(PuzzleInfo::new(7, "Test", input));
+        let file_system = puzzle.get_file_system().unwrap();
+        assert_eq!(vec![4294967294], file_system.get_dir_sizes());
+        puzzle.params.disk_space = 5000000000;
+        puzzle.params.needed_space = 1000000000;
         assert_eq!(
-            24933642,
-            SeventhPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .size_of_dir_to_delete(70000000, 30000000)
-        )
+            Ok(Answer::from(4294967294i64)),
+            puzzle.solve(&file_system, Part::Second)
+        );
     }
 
     #[test]
-    fn total_size_of_dirs_at_most() {
+    fn not_enough_space_freed() {
+        let mut puzzle = SeventhPuzzle::new(get_puzzle_info());
+        puzzle.params.needed_space = 100000000;
+        let file_system = puzzle.get_file_system().unwrap();
         assert_eq!(
-            95437,
-            SeventhPuzzle {
-                puzzle: get_puzzle_info(),
-            }
-            .total_size_of_dirs_to_delete(100000)
-        )
+            Err(SolveError::NoSolution(String::from(
+                "No directory frees up enough space for the update of 100000000"
+            ))),
+            puzzle.solve(&file_system, Part::Second)
+        );
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "$ cd /
-            $ ls
-            dir a
-            14848514 b.txt
-            8504156 c.dat
-            dir d
-            $ cd a
-            $ ls
-            dir e
-            29116 f
-            2557 g
-            62596 h.lst
-            $ cd e
-            $ ls
-            584 i
-            $ cd ..
-            $ cd ..
-            $ cd d
-            $ ls
-            4060174 j
-            8033020 d.log
-            5626152 d.ext
-            7214296 k",
-            ),
-        }
+        PuzzleInfo::new(7, "Test", EXAMPLES[0].input)
     }
 }
@@ -1,58 +1,69 @@
-use std::{collections::HashMap};
-
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError, Answer, Direction, Example, Generated, Grid, Part, Point2, Puzzle,
+    PuzzleInfo, Rng, SolveError,
+};
 
 pub struct EighthPuzzle {
     puzzle: PuzzleInfo,
 }
 
-impl Solution for EighthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.get_forest().visible_trees_from_outside(),
-            self.get_forest().highest_scenic_score_for_any_tree(),
-        );
+register!(
+    8,
+    "Eighth Puzzle - Treetop Tree House",
+    "8.txt",
+    EighthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[Example {
+    input: "30373
+25512
+65332
+33549
+35390",
+    answers: [Some("21"), Some("8")],
+    params: &[],
+}];
+
+impl Puzzle for EighthPuzzle {
+    type Model = Forest;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
     }
-}
 
-struct Forest {
-    trees: HashMap<TreeCoordinate, TreeHeight>,
-    total_lines: u32,
-    total_cols: u32,
-    visibility_direction_variants: [VisibilityDirection; 4],
-}
+    fn parse(&self) -> Result<Forest, ParseError> {
+        self.get_forest()
+    }
 
-impl Forest {
-    fn new(trees: Vec<Tree>, total_lines: u32, total_cols: u32) -> Self {
-        let mut trees_as_map = HashMap::new();
-        trees.iter().for_each(|tree| {
-            trees_as_map.insert(tree.coordinate, tree.height);
-        });
-
-        Self {
-            trees: trees_as_map,
-            total_lines,
-            total_cols,
-            visibility_direction_variants: [
-                VisibilityDirection::TOP,
-                VisibilityDirection::RIGHT,
-                VisibilityDirection::BOTTOM,
-                VisibilityDirection::LEFT,
-            ],
+    fn summary(&self, forest: &Forest) -> String {
+        format!("{}x{} trees", forest.trees.height(), forest.trees.width())
+    }
+
+    fn solve(&self, forest: &Forest, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(forest.visible_trees_from_outside().into()),
+            Part::Second => Ok(forest.highest_scenic_score_for_any_tree().into()),
         }
     }
+}
+
+pub struct Forest {
+    trees: Grid<TreeHeight>,
+}
 
+impl Forest {
     fn visible_trees_from_outside(&self) -> u32 {
         let mut visible_trees = 0;
         for (coordinate, height) in self.trees.iter() {
-            if self.is_edge(*coordinate) {
+            if self.trees.is_edge(coordinate) {
                 visible_trees += 1;
                 continue;
             }
 
-            for variant in self.visibility_direction_variants.iter() {
-                if self.is_visible(*coordinate, *height, variant).0 {
+            for direction in Direction::ORTHOGONAL {
+                if self.is_visible(coordinate, *height, direction).0 {
                     visible_trees += 1;
                     break;
                 }
@@ -62,16 +73,18 @@ impl Forest {
     }
 
     fn highest_scenic_score_for_any_tree(&self) -> u32 {
-        let mut highest_scenic_score = 1;
+        // the trees on the edge see nothing in one of the directions, so a forest made of
+        // them only has the score 0
+        let mut highest_scenic_score = 0;
         for (coordinate, height) in self.trees.iter() {
-            if self.is_edge(*coordinate) {
+            if self.trees.is_edge(coordinate) {
                 continue;
             }
 
             let mut scenic_score = 1;
 
-            for variant in self.visibility_direction_variants.iter() {
-                scenic_score *= self.is_visible(*coordinate, *height, variant).1
+            for direction in Direction::ORTHOGONAL {
+                scenic_score *= self.is_visible(coordinate, *height, direction).1
             }
 
             if scenic_score > highest_scenic_score {
@@ -81,38 +94,18 @@ impl Forest {
         highest_scenic_score
     }
 
-    fn is_edge(&self, coordinate: TreeCoordinate) -> bool {
-        (coordinate.0 == 0 || coordinate.1 == 0)
-            || (coordinate.0 == self.total_lines || coordinate.1 == self.total_cols)
-    }
-
     fn is_visible(
         &self,
         coordinate: TreeCoordinate,
         height: u32,
-        from_direction: &VisibilityDirection,
+        from_direction: Direction,
     ) -> (bool, u32) {
         let mut trees_in_view = 0;
-        let sequences: Vec<u32> = match from_direction {
-            VisibilityDirection::TOP => (0..coordinate.0).rev().step_by(1).collect(),
-            VisibilityDirection::RIGHT => (coordinate.1 + 1..=self.total_cols).step_by(1).collect(),
-            VisibilityDirection::BOTTOM => (coordinate.0 + 1..=self.total_lines).step_by(1).collect(),
-            VisibilityDirection::LEFT => (0..coordinate.1).rev().step_by(1).collect(),
-        };
-
-        for i in sequences {
-            let upfront_tree = match from_direction {
-                VisibilityDirection::TOP | VisibilityDirection::BOTTOM => {
-                    self.trees.get(&(i, coordinate.1))
-                }
-                VisibilityDirection::LEFT | VisibilityDirection::RIGHT => {
-                    self.trees.get(&(coordinate.0, i))
-                }
-            };
-
+        // the view ends at the edge of the forest
+        for (_, upfront_tree) in self.trees.ray(coordinate, from_direction) {
             trees_in_view += 1;
 
-            if *upfront_tree.unwrap() >= height {
+            if *upfront_tree >= height {
                 return (false, trees_in_view);
             }
         }
@@ -121,95 +114,115 @@ impl Forest {
     }
 }
 
-enum VisibilityDirection {
-    TOP,
-    RIGHT,
-    BOTTOM,
-    LEFT,
-}
-
-type TreeCoordinate = (u32, u32);
+type TreeCoordinate = Point2<usize>;
 type TreeHeight = u32;
-struct Tree {
-    height: TreeHeight,
-    coordinate: TreeCoordinate,
-}
-
-impl Tree {
-    fn new(line: u32, col: u32, height: u32) -> Self {
-        Self {
-            coordinate: (line, col),
-            height,
-        }
-    }
-}
 
 impl EighthPuzzle {
-    pub fn new() -> Self {
-        Self {
-            puzzle: PuzzleInfo::new("Eighth Puzzle - Treetop Tree House", "./inputs/8.txt"),
-        }
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
     }
 
-    fn get_forest(&self) -> Forest {
-        let mut trees: Vec<Tree> = Vec::new();
-        let mut line_no = 0;
+    fn get_forest(&self) -> Result<Forest, ParseError> {
+        let lines = self.puzzle.lines().filter(|line| !line.text.is_empty());
+        let trees = Grid::parse(lines, "trees", |line, offset, c| {
+            c.to_digit(10)
+                .ok_or_else(|| line.error(offset, "a tree height 0-9"))
+        })?;
 
-        for line in self.puzzle.input.lines() {
-            for (col, c) in line.trim().chars().enumerate() {
-                trees.push(Tree::new(line_no, col as u32, c.to_digit(10).unwrap()));
-            }
-            line_no += 1;
+        if trees.is_empty() {
+            return Err(self.puzzle.end_of_input_error("a row of trees"));
         }
 
-        Forest::new(
-            trees,
-            line_no - 1,
-            (self.puzzle.input.lines().next().unwrap().len() - 1) as u32,
-        )
+        Ok(Forest { trees })
     }
 }
 
+// grid of the tree heights
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let side = size.clamp(1, 99);
+    let (rows, cols) = (
+        rng.range(side.div_ceil(2)..=side),
+        rng.range(side.div_ceil(2)..=side),
+    );
+    let forest: Vec<String> = (0..rows)
+        .map(|_| (0..cols).map(|_| rng.range(b'0'..=b'9') as char).collect())
+        .collect();
+    forest.join("\n").into()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::PuzzleInfo;
+    use crate::{property, PuzzleInfo};
 
     use super::*;
 
     #[test]
-    fn highest_scenic_score_for_any_tree() {
-        assert_eq!(
-            8,
-            EighthPuzzle {
-                puzzle: get_puzzle_info()
-            }
-            .get_forest()
-            .highest_scenic_score_for_any_tree()
-        )
+    fn matches_reference() {
+        // the smallest forests are made of the edges only
+        for size in [1, 2, 3, 12] {
+            property::check(&REGISTRATION, 50, size, &|generated, puzzle| {
+                let (visible, scenic_score) = reference(&generated.input);
+                property::compare(puzzle, Part::First, visible)?;
+                property::compare(puzzle, Part::Second, scenic_score)
+            });
+        }
     }
 
     #[test]
-    fn visible_trees_from_outside() {
-        assert_eq!(
-            21,
-            EighthPuzzle {
-                puzzle: get_puzzle_info()
+    fn invalid_tree_height() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("65332", "65_32");
+        let error = EighthPuzzle { puzzle }.get_forest().err().unwrap();
+        assert_eq!((3, 3), (error.line, error.column));
+        assert_eq!("a tree height 0-9", error.expected);
+    }
+
+    #[test]
+    fn uneven_rows() {
+        let mut puzzle = get_puzzle_info();
+        puzzle.input = puzzle.input.replace("33549", "3354");
+        let error = EighthPuzzle { puzzle }.get_forest().err().unwrap();
+        assert_eq!((4, 5), (error.line, error.column));
+        assert_eq!("a row of 5 trees", error.expected);
+    }
+
+    // every tree is compared with all the trees in the four directions
+    fn reference(input: &str) -> (usize, usize) {
+        let forest: Vec<Vec<u32>> = input
+            .lines()
+            .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
+            .collect();
+        let (rows, cols) = (forest.len(), forest[0].len());
+        let (mut visible, mut scenic_score) = (0, 0);
+        for row in 0..rows {
+            for col in 0..cols {
+                let height = forest[row][col];
+                let views: [Vec<u32>; 4] = [
+                    (0..row).rev().map(|idx| forest[idx][col]).collect(),
+                    (row + 1..rows).map(|idx| forest[idx][col]).collect(),
+                    (0..col).rev().map(|idx| forest[row][idx]).collect(),
+                    (col + 1..cols).map(|idx| forest[row][idx]).collect(),
+                ];
+                if views
+                    .iter()
+                    .any(|view| view.iter().all(|tree| *tree < height))
+                {
+                    visible += 1;
+                }
+                let score = views
+                    .iter()
+                    .map(|view| match view.iter().position(|tree| *tree >= height) {
+                        Some(idx) => idx + 1,
+                        None => view.len(),
+                    })
+                    .product();
+                scenic_score = scenic_score.max(score);
             }
-            .get_forest()
-            .visible_trees_from_outside()
-        )
+        }
+        (visible, scenic_score)
     }
 
     fn get_puzzle_info() -> PuzzleInfo {
-        PuzzleInfo {
-            name: String::from("Test"),
-            input: String::from(
-                "30373
-                25512
-                65332
-                33549
-                35390",
-            ),
-        }
+        PuzzleInfo::new(8, "Test", EXAMPLES[0].input)
     }
 }
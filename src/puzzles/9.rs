@@ -2,45 +2,145 @@ use std::collections::HashSet;
 
 use regex::Regex;
 
-use crate::{util::print_solution, PuzzleInfo, Solution};
+use crate::{
+    parse::ParseError,
+    visualize::{self, Pixel, Viewport},
+    Answer, Direction, Example, Frame, Generated, Parameters, Part, Point2, Puzzle, PuzzleInfo,
+    Rng, SolveError, Visualize,
+};
 
 pub struct NinthPuzzle {
     puzzle: PuzzleInfo,
+    params: NinthParameters,
 }
 
-impl Solution for NinthPuzzle {
-    fn solution(&self) {
-        print_solution(
-            &self.puzzle.name,
-            self.get_grid(1).positions_visited_by_tail_last_knot(),
-            self.get_grid(9).positions_visited_by_tail_last_knot(),
-        );
+parameters!(NinthParameters {
+    // the number of knots of the rope including the head in the first and the second part
+    first_knots: usize = 2,
+    second_knots: usize = 10,
+});
+
+register!(
+    9,
+    "Ninth Puzzle - Rope Bridge",
+    "9.txt",
+    NinthPuzzle,
+    EXAMPLES,
+    generate
+);
+
+const EXAMPLES: &[Example] = &[
+    Example {
+        input: "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2",
+        answers: [Some("13"), Some("1")],
+        params: &[],
+    },
+    Example {
+        input: "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20",
+        answers: [None, Some("36")],
+        params: &[],
+    },
+];
+
+impl Puzzle for NinthPuzzle {
+    // the motions of the head, the knots are only placed when solving the part
+    type Model = Vec<Motion>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Vec<Motion>, ParseError> {
+        self.get_motions()
+    }
+
+    fn summary(&self, motions: &Vec<Motion>) -> String {
+        format!("{} motions", motions.len())
+    }
+
+    fn solve(&self, motions: &Vec<Motion>, part: Part) -> Result<Answer, SolveError> {
+        Ok(GridOfPositions::build_grid(self.tail_length(part))
+            .positions_visited_by_tail_last_knot(motions)
+            .into())
+    }
+
+    fn parameters(&mut self) -> Option<&mut dyn Parameters> {
+        Some(&mut self.params)
     }
-}
 
-type Position = (i32, i32);
-enum Movement {
-    Up(i32),
-    Right(i32),
-    Down(i32),
-    Left(i32),
+    fn visualization(&self) -> Option<&dyn Visualize<Model = Vec<Motion>>> {
+        Some(self)
+    }
 }
 
-impl Movement {
-    fn get_value(&self) -> i32 {
-        match self {
-            Movement::Up(n) => *n,
-            Movement::Right(n) => *n,
-            Movement::Down(n) => *n,
-            Movement::Left(n) => *n,
-        }
+const VISITED: Pixel = Pixel::new('#', [70, 70, 150]);
+const HEAD: Pixel = Pixel::new('H', [230, 60, 60]);
+const KNOT_COLOR: [u8; 3] = [240, 200, 60];
+
+impl Visualize for NinthPuzzle {
+    type Model = Vec<Motion>;
+
+    // the rope after every step, the positions visited by the last knot stay behind it
+    fn frames(&self, motions: &Vec<Motion>, part: Part, sink: &mut dyn FnMut(&Frame)) {
+        let point = |position: Position| Point2::<i64>::new(position.x.into(), position.y.into());
+        let (mut top_left, mut bottom_right) = (Point2::default(), Point2::default());
+        GridOfPositions::build_grid(0).follow(motions, &mut |head, _| {
+            let head = point(head);
+            top_left = Point2::new(head.x.min(top_left.x), head.y.min(top_left.y));
+            bottom_right = Point2::new(head.x.max(bottom_right.x), head.y.max(bottom_right.y));
+        });
+
+        let viewport = Viewport::new(top_left, bottom_right, 120);
+        let mut frame = viewport.frame();
+        let mut visited = HashSet::from([viewport.project(Point2::default())]);
+        let mut rope: Vec<Point2<i64>> = Vec::new();
+        GridOfPositions::build_grid(self.tail_length(part)).follow(motions, &mut |head, knots| {
+            for pixel_point in &rope {
+                let pixel = match visited.contains(pixel_point) {
+                    true => VISITED,
+                    false => visualize::EMPTY,
+                };
+                frame.set(*pixel_point, pixel);
+            }
+            visited.insert(viewport.project(point(*knots.last().unwrap_or(&head))));
+            rope = std::iter::once(head)
+                .chain(knots.iter().copied())
+                .map(|position| viewport.project(point(position)))
+                .collect();
+            // the knots closer to the head are drawn over the further ones
+            for (idx, pixel_point) in rope.iter().enumerate().rev() {
+                let pixel = match char::from_digit(idx as u32 % 10, 10) {
+                    Some(digit) if idx > 0 => Pixel::new(digit, KNOT_COLOR),
+                    _ => HEAD,
+                };
+                frame.set(*pixel_point, pixel);
+            }
+            sink(&frame);
+        });
     }
 }
 
+type Position = Point2<i32>;
+// the direction of the head and the number of steps
+pub type Motion = (Direction, i32);
+
 struct GridOfPositions {
     head: Position,
     tail: Tail,
-    movements: Vec<Movement>,
 }
 
 struct Tail {
@@ -51,72 +151,55 @@ struct Tail {
 impl Tail {
     fn new(length: usize) -> Self {
         Self {
-            last_knot_pos: (0, 0),
-            knots: vec![(0, 0); length],
-        }
-    }
-
-    fn increment_or_decrement_knot(
-        curr_knot_pos_coordinate: &mut i32,
-        previous_knot_pos_coordinate: i32,
-    ) {
-        if previous_knot_pos_coordinate > *curr_knot_pos_coordinate {
-            *curr_knot_pos_coordinate += 1;
-        } else {
-            *curr_knot_pos_coordinate -= 1;
+            last_knot_pos: Position::default(),
+            knots: vec![Position::default(); length],
         }
     }
 }
 
 impl GridOfPositions {
-    fn build_grid(movements: Vec<Movement>, tail_length: usize) -> Self {
+    fn build_grid(tail_length: usize) -> Self {
         Self {
-            head: (0, 0),
+            head: Position::default(),
             tail: Tail::new(tail_length),
-            movements,
         }
     }
 
-    fn positions_visited_by_tail_last_knot(&mut self) -> u32 {
+    fn positions_visited_by_tail_last_knot(&mut self, motions: &[Motion]) -> u32 {
+        self.follow(motions, &mut |_, _| {})
+    }
+
+    // the observer sees the head and the knots of the tail after every step
+    fn follow(
+        &mut self,
+        motions: &[Motion],
+        observe: &mut dyn FnMut(Position, &[Position]),
+    ) -> u32 {
         let mut visited_positions = HashSet::new();
         // starting position
-        visited_positions.insert((0, 0));
-        for movement in self.movements.iter() {
-            for _ in 0..movement.get_value() {
-                match movement {
-                    Movement::Up(_) => self.head.0 += 1,
-                    Movement::Right(_) => self.head.1 += 1,
-                    Movement::Down(_) => self.head.0 -= 1,
-                    Movement::Left(_) => self.head.1 -= 1,
-                }
+        visited_positions.insert(Position::default());
+        for (direction, steps) in motions.iter() {
+            for _ in 0..*steps {
+                self.head = self.head + direction.offset();
 
                 let mut previous_knot = self.head;
                 let knots = &mut self.tail.knots;
                 let knots_len = knots.len();
                 for (idx, knot) in knots.iter_mut().enumerate() {
-                    let is_line_gap = (previous_knot.0 - knot.0).abs() > 1;
-                    let is_col_gap = (previous_knot.1 - knot.1).abs() > 1;
-
-                    if !(is_line_gap || is_col_gap) {
+                    // the knot still touching the previous one stays, and so do the following
+                    if knot.chebyshev(previous_knot) <= 1 {
                         break;
-                    } else if is_line_gap && is_col_gap {
-                        Tail::increment_or_decrement_knot(&mut knot.0, previous_knot.0);
-                        Tail::increment_or_decrement_knot(&mut knot.1, previous_knot.1);
-                    } else if is_line_gap {
-                        knot.1 = previous_knot.1;
-                        Tail::increment_or_decrement_knot(&mut knot.0, previous_knot.0);
-                    } else if is_col_gap {
-                        knot.0 = previous_knot.0;
-                        Tail::increment_or_decrement_knot(&mut knot.1, previous_knot.1);
                     }
+                    *knot = *knot + (previous_knot - *knot).signum();
 
                     if idx == knots_len - 1 {
-                        self.tail.last_knot_pos = (knot.0, knot.1);
+                        self.tail.last_knot_pos = *knot;
                         visited_positions.insert(self.tail.last_knot_pos);
                     }
 
                     previous_knot = *knot;
                 }
+                observe(self.head, &self.tail.knots);
             }
         }
         visited_positions.len() as u32
@@ -124,34 +207,55 @@ impl GridOfPositions {
 }
 
 impl NinthPuzzle {
-    pub fn new() -> Self {
+    pub fn new(puzzle: PuzzleInfo) -> Self {
         Self {
-            puzzle: PuzzleInfo::new("Ninth Puzzle - Rope Bridge", "./inputs/9.txt"),
+            puzzle,
+            params: NinthParameters::default(),
         }
     }
 
-    fn get_grid(&self, tail_length: usize) -> GridOfPositions {
-        let mut movements = Vec::new();
-        let re_movement = Regex::new(r"([A-Z]) (\d+)").unwrap();
-
-        self.puzzle.input.lines().for_each(|line| {
-            let line = line.trim();
-            let movement_captures = re_movement.captures(line).unwrap();
-            let distance: i32 = *&movement_captures[2].parse().unwrap();
-            let movement = match &movement_captures[1].chars().next().unwrap() {
-                'U' => Movement::Up(distance),
-                'R' => Movement::Right(distance),
-                'D' => Movement::Down(distance),
-                'L' => Movement::Left(distance),
-                _ => unreachable!(),
+    fn tail_length(&self, part: Part) -> usize {
+        let knots = match part {
+            Part::First => self.params.first_knots,
+            Part::Second => self.params.second_knots,
+        };
+        knots.saturating_sub(1)
+    }
+
+    fn get_motions(&self) -> Result<Vec<Motion>, ParseError> {
+        let mut motions = Vec::new();
+        let re_motion = Regex::new(r"^([A-Z]) (\d+)$").unwrap();
+
+        for line in self.puzzle.lines().filter(|line| !line.text.is_empty()) {
+            let motion_captures = re_motion
+                .captures(line.text)
+                .ok_or_else(|| line.error(0, "a movement like `R 4`"))?;
+            let distance: i32 = line.parse(2, &motion_captures[2], "a distance")?;
+            let direction = match motion_captures[1].chars().next().unwrap() {
+                letter @ ('U' | 'R' | 'D' | 'L') => Direction::from_char(letter).unwrap(),
+                _ => return Err(line.error(0, "a direction U, R, D or L")),
             };
-            movements.push(movement);
-        });
+            motions.push((direction, distance));
+        }
 
-        GridOfPositions::build_grid(movements, tail_length)
+        Ok(motions)
     }
 }
 
+// motions of the head of the rope
+fn generate(rng: &mut Rng, size: usize) -> Generated {
+    let motions: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.choose(&['U', 'R', 'D', 'L']),
+                rng.range(1..=20)
+            )
+        })
+        .collect();
+    motions.join("\n").into()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::PuzzleInfo;
@@ -159,49 +263,30 @@ mod tests {
     use super::*;
 
     #[test]
-    fn positions_visited_by_tail() {
-        assert_eq!(
-            13,
-            NinthPuzzle {
-                puzzle: get_puzzle_info(
-                    "R 4
-                U 4
-                L 3
-                D 1
-                R 4
-                D 1
-                L 5
-                R 2"
-                ),
-            }
-            .get_grid(1)
-            .positions_visited_by_tail_last_knot()
-        );
+    fn unknown_direction() {
+        let error = NinthPuzzle::new(get_puzzle_info("R 4\nX 4"))
+            .get_motions()
+            .err()
+            .unwrap();
+        assert_eq!((2, 1), (error.line, error.column));
+        assert_eq!("a direction U, R, D or L", error.expected);
     }
 
     #[test]
-    fn positions_visited_by_last_knot_of_extended_tail() {
-        assert_eq!(
-            36,
-            NinthPuzzle {
-                puzzle: get_puzzle_info(
-                    "R 5
-                    U 8
-                    L 8
-                    D 3
-                    R 17
-                    D 10
-                    L 25
-                    U 20"
-                ),
-            }
-            .get_grid(9)
-            .positions_visited_by_tail_last_knot()
-        );
+    fn rope_drawn_every_step() {
+        let puzzle = NinthPuzzle::new(get_puzzle_info(EXAMPLES[0].input));
+        let motions = puzzle.get_motions().unwrap();
+        let mut frames = Vec::new();
+        puzzle.frames(&motions, Part::First, &mut |frame| {
+            frames.push(frame.to_string())
+        });
+        assert_eq!(24, frames.len());
+        assert_eq!("..##..\n...##.\n.1H##.\n....#.\n####..", frames[23]);
     }
 
     fn get_puzzle_info(input: &str) -> PuzzleInfo {
         PuzzleInfo {
+            day: 9,
             name: String::from("Test"),
             input: String::from(input),
         }
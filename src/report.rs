@@ -0,0 +1,254 @@
+use std::{fmt, str::FromStr, time::Duration};
+
+use code_advent_2022::{Answer, Part};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Format {
+    #[default]
+    Human,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "Invalid format `{value}`, expected human, json or csv"
+            )),
+        }
+    }
+}
+
+pub enum Outcome {
+    Solved(Answer),
+    Failed(String),
+    // the part was not selected
+    Skipped,
+}
+
+pub struct PartReport<'a> {
+    pub name: &'a str,
+    pub day: u8,
+    pub part: Part,
+    pub outcome: Outcome,
+    pub elapsed: Option<Duration>,
+}
+
+impl PartReport<'_> {
+    fn status(&self) -> &'static str {
+        match self.outcome {
+            Outcome::Solved(_) => "ok",
+            Outcome::Failed(_) => "error",
+            Outcome::Skipped => "skipped",
+        }
+    }
+
+    fn elapsed_millis(&self) -> Option<f64> {
+        self.elapsed.map(|elapsed| elapsed.as_secs_f64() * 1000.0)
+    }
+}
+
+pub fn print(format: Format, reports: &[PartReport]) {
+    match format {
+        Format::Human => {
+            for report in reports {
+                match &report.outcome {
+                    Outcome::Solved(answer) => print_answer(report.name, report.part, answer),
+                    Outcome::Failed(error) => print_error(report.name, report.part, error),
+                    Outcome::Skipped => {}
+                }
+            }
+        }
+        Format::Json => println!("{}", json(reports)),
+        Format::Csv => print!("{}", csv(reports)),
+    }
+}
+
+// the counts of the parts by their status, the machine readable formats keep it out of stdout
+pub fn print_summary(format: Format, reports: &[PartReport]) {
+    let summary = summary(reports);
+    match format {
+        Format::Human => println!("{summary}"),
+        Format::Json | Format::Csv => eprintln!("{summary}"),
+    }
+}
+
+fn summary(reports: &[PartReport]) -> String {
+    let count = |status| {
+        reports
+            .iter()
+            .filter(|report| report.status() == status)
+            .count()
+    };
+    format!(
+        "{} part(s) solved, {} failed, {} skipped",
+        count("ok"),
+        count("error"),
+        count("skipped")
+    )
+}
+
+pub fn print_answer(puzzle_name: &str, part: Part, answer: &Answer) {
+    if answer.is_multiline() {
+        println!("{puzzle_name}: Solution for the {part} part is:\n{answer}");
+    } else {
+        println!("{puzzle_name}: Solution for the {part} part is: {answer}.");
+    }
+}
+
+pub fn print_error(puzzle_name: &str, part: Part, error: &dyn fmt::Display) {
+    eprintln!("{puzzle_name}: Failed to solve the {part} part. {error}");
+}
+
+fn json(reports: &[PartReport]) -> String {
+    let objects: Vec<String> = reports
+        .iter()
+        .map(|report| {
+            let answer = match &report.outcome {
+                Outcome::Solved(Answer::Integer(n)) => n.to_string(),
+                Outcome::Solved(answer) => json_string(&answer.to_string()),
+                _ => String::from("null"),
+            };
+            let error = match &report.outcome {
+                Outcome::Failed(error) => json_string(error),
+                _ => String::from("null"),
+            };
+            let elapsed = match report.elapsed_millis() {
+                Some(millis) => format!("{millis:.3}"),
+                None => String::from("null"),
+            };
+            format!(
+                "  {{\"name\": {}, \"day\": {}, \"part\": {}, \"status\": \"{}\", \"answer\": {answer}, \"error\": {error}, \"elapsed_ms\": {elapsed}}}",
+                json_string(report.name),
+                report.day,
+                report.part.number(),
+                report.status()
+            )
+        })
+        .collect();
+
+    match objects.is_empty() {
+        true => String::from("[]"),
+        false => format!("[\n{}\n]", objects.join(",\n")),
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv(reports: &[PartReport]) -> String {
+    let mut content = String::from("name,day,part,status,answer,error,elapsed_ms\n");
+    for report in reports {
+        let (answer, error) = match &report.outcome {
+            Outcome::Solved(answer) => (answer.to_string(), String::new()),
+            Outcome::Failed(error) => (String::new(), error.clone()),
+            Outcome::Skipped => (String::new(), String::new()),
+        };
+        let elapsed = report
+            .elapsed_millis()
+            .map(|millis| format!("{millis:.3}"))
+            .unwrap_or_default();
+        content.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_field(report.name),
+            report.day,
+            report.part.number(),
+            report.status(),
+            csv_field(&answer),
+            csv_field(&error),
+            elapsed
+        ));
+    }
+    content
+}
+
+// fields containing separators, quotes or new lines are quoted
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_reports() -> Vec<PartReport<'static>> {
+        vec![
+            PartReport {
+                name: "Tenth Puzzle",
+                day: 10,
+                part: Part::First,
+                outcome: Outcome::Solved(Answer::from(13140)),
+                elapsed: Some(Duration::from_micros(1500)),
+            },
+            PartReport {
+                name: "Tenth Puzzle",
+                day: 10,
+                part: Part::Second,
+                outcome: Outcome::Failed(String::from("expected `noop`, found \"x\"")),
+                elapsed: Some(Duration::from_micros(20)),
+            },
+            PartReport {
+                name: "Eleventh Puzzle",
+                day: 11,
+                part: Part::First,
+                outcome: Outcome::Skipped,
+                elapsed: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn json_output() {
+        assert_eq!(
+            "[
+  {\"name\": \"Tenth Puzzle\", \"day\": 10, \"part\": 1, \"status\": \"ok\", \"answer\": 13140, \"error\": null, \"elapsed_ms\": 1.500},
+  {\"name\": \"Tenth Puzzle\", \"day\": 10, \"part\": 2, \"status\": \"error\", \"answer\": null, \"error\": \"expected `noop`, found \\\"x\\\"\", \"elapsed_ms\": 0.020},
+  {\"name\": \"Eleventh Puzzle\", \"day\": 11, \"part\": 1, \"status\": \"skipped\", \"answer\": null, \"error\": null, \"elapsed_ms\": null}
+]",
+            json(&get_reports())
+        );
+    }
+
+    #[test]
+    fn summary_of_reports() {
+        assert_eq!(
+            "1 part(s) solved, 1 failed, 1 skipped",
+            summary(&get_reports())
+        );
+    }
+
+    #[test]
+    fn csv_output() {
+        assert_eq!(
+            "name,day,part,status,answer,error,elapsed_ms
+Tenth Puzzle,10,1,ok,13140,,1.500
+Tenth Puzzle,10,2,error,,\"expected `noop`, found \"\"x\"\"\",0.020
+Eleventh Puzzle,11,1,skipped,,,
+",
+            csv(&get_reports())
+        );
+    }
+}
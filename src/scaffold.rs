@@ -0,0 +1,284 @@
+use std::{fs, path::Path};
+
+use code_advent_2022::{registry, InputSource};
+
+use crate::cli::NewDayOptions;
+
+const ORDINALS: [&str; 25] = [
+    "First",
+    "Second",
+    "Third",
+    "Fourth",
+    "Fifth",
+    "Sixth",
+    "Seventh",
+    "Eighth",
+    "Ninth",
+    "Tenth",
+    "Eleventh",
+    "Twelfth",
+    "Thirteenth",
+    "Fourteenth",
+    "Fifteenth",
+    "Sixteenth",
+    "Seventeenth",
+    "Eighteenth",
+    "Nineteenth",
+    "Twentieth",
+    "Twenty-first",
+    "Twenty-second",
+    "Twenty-third",
+    "Twenty-fourth",
+    "Twenty-fifth",
+];
+
+// the names of the generated puzzle, e.g. `SixteenthPuzzle` declared in `sixteenth_puzzle`
+struct Names {
+    ordinal: &'static str,
+    puzzle_type: String,
+    module: String,
+}
+
+impl Names {
+    fn new(day: u8) -> Result<Self, String> {
+        let ordinal = match day {
+            1..=25 => ORDINALS[day as usize - 1],
+            _ => return Err(format!("Invalid day `{day}`, expected 1 to 25")),
+        };
+        Ok(Self {
+            ordinal,
+            puzzle_type: ordinal
+                .split('-')
+                .map(|word| word[..1].to_uppercase() + &word[1..])
+                .chain(Some(String::from("Puzzle")))
+                .collect(),
+            module: format!("{}_puzzle", ordinal.to_lowercase().replace('-', "_")),
+        })
+    }
+}
+
+// creates the module of the puzzle with an empty input and registers it with the runner,
+// the sources are expected in the crate the binary was built from
+pub fn run(options: &NewDayOptions) -> Result<(), String> {
+    let names = Names::new(options.day)?;
+    if registry()
+        .iter()
+        .any(|registration| registration.day == options.day)
+    {
+        return Err(format!(
+            "There is already a puzzle registered for day {}",
+            options.day
+        ));
+    }
+
+    let src_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let module_path = src_dir.join("puzzles").join(format!("{}.rs", options.day));
+    if module_path.exists() {
+        return Err(format!(
+            "The module {} already exists",
+            module_path.display()
+        ));
+    }
+    let registry_path = src_dir.join("puzzles.rs");
+    let registry_source = fs::read_to_string(&registry_path)
+        .map_err(|error| format!("Not able to read {}: {error}", registry_path.display()))?;
+    let registry_source = register_module(&registry_source, options.day, &names.module)?;
+
+    let input_dir = InputSource::new(options.inputs_dir.as_deref(), None)
+        .dir()
+        .to_path_buf();
+    let input_path = input_dir.join(format!("{}.txt", options.day));
+
+    write(
+        &module_path,
+        &module_source(options.day, &options.title, &names),
+    )?;
+    write(&registry_path, &registry_source)?;
+    if !input_path.exists() {
+        fs::create_dir_all(&input_dir)
+            .map_err(|error| format!("Not able to create {}: {error}", input_dir.display()))?;
+        write(&input_path, "")?;
+    }
+
+    println!(
+        "{} Puzzle - {} created in {}, its input belongs to {}",
+        names.ordinal,
+        options.title,
+        module_path.display(),
+        input_path.display()
+    );
+    Ok(())
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content)
+        .map_err(|error| format!("Not able to write {}: {error}", path.display()))
+}
+
+fn module_source(day: u8, title: &str, names: &Names) -> String {
+    let name = format!("{} Puzzle - {title}", names.ordinal);
+    // the arguments of the registration never fit on a single line of rustfmt
+    let registration = [
+        day.to_string(),
+        format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\"")),
+        format!("\"{day}.txt\""),
+        names.puzzle_type.clone(),
+        String::from("EXAMPLES"),
+        String::from("generate"),
+    ]
+    .map(|argument| format!("    {argument}"))
+    .join(",\n");
+
+    MODULE_TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{registration}", &registration)
+        .replace("{puzzle_type}", &names.puzzle_type)
+}
+
+const MODULE_TEMPLATE: &str = r#"use crate::{
+    parse::ParseError, Answer, Example, Generated, Part, Puzzle, PuzzleInfo, Rng, SolveError,
+};
+
+// for more details check the https://adventofcode.com/2022/day/{day}
+pub struct {puzzle_type} {
+    puzzle: PuzzleInfo,
+}
+
+register!(
+{registration}
+);
+
+// the answers are filled in once the puzzle is solved
+const EXAMPLES: &[Example] = &[Example {
+    input: "",
+    answers: [None, None],
+    params: &[],
+}];
+
+impl Puzzle for {puzzle_type} {
+    // the lines of the input until the puzzle gets its own model
+    type Model = Vec<String>;
+
+    fn puzzle_info(&self) -> &PuzzleInfo {
+        &self.puzzle
+    }
+
+    fn parse(&self) -> Result<Vec<String>, ParseError> {
+        Ok(self
+            .puzzle
+            .lines()
+            .map(|line| String::from(line.text))
+            .collect())
+    }
+
+    fn summary(&self, lines: &Vec<String>) -> String {
+        format!("{} lines", lines.len())
+    }
+
+    fn solve(&self, lines: &Vec<String>, part: Part) -> Result<Answer, SolveError> {
+        match part {
+            Part::First => Ok(self.first_part(lines).into()),
+            Part::Second => Ok(self.second_part(lines).into()),
+        }
+    }
+}
+
+impl {puzzle_type} {
+    pub fn new(puzzle: PuzzleInfo) -> Self {
+        Self { puzzle }
+    }
+
+    fn first_part(&self, _lines: &[String]) -> i64 {
+        todo!("solve the first part of the day {day}")
+    }
+
+    fn second_part(&self, _lines: &[String]) -> i64 {
+        todo!("solve the second part of the day {day}")
+    }
+}
+
+// the generated inputs are solved by the tests, they are filled in with the puzzle
+fn generate(_rng: &mut Rng, _size: usize) -> Generated {
+    todo!("generate the inputs of the day {day}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_input() {
+        let puzzle = {puzzle_type}::new(get_puzzle_info());
+        assert!(puzzle.parse().is_ok());
+    }
+
+    fn get_puzzle_info() -> PuzzleInfo {
+        PuzzleInfo::new({day}, "Test", EXAMPLES[0].input)
+    }
+}
+"#;
+
+// lists the module at the end of the puzzles, which declares it and adds it to the registry
+fn register_module(source: &str, day: u8, module: &str) -> Result<String, String> {
+    let invalid =
+        || String::from("The registry of the puzzles in src/puzzles.rs is not recognized");
+
+    let puzzles_start = source.find("\npuzzles! {\n").ok_or_else(invalid)?;
+    let puzzles_end = puzzles_start + source[puzzles_start..].find("\n}").ok_or_else(invalid)? + 1;
+
+    Ok(format!(
+        "{}    #[path = \"puzzles/{day}.rs\"]\n    pub mod {module};\n{}",
+        &source[..puzzles_end],
+        &source[puzzles_end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_of_the_day() {
+        let names = Names::new(21).unwrap();
+        assert_eq!("TwentyFirstPuzzle", names.puzzle_type);
+        assert_eq!("twenty_first_puzzle", names.module);
+        assert!(Names::new(26).is_err());
+
+        let source = module_source(16, "Proboscidea \"Volcanium\"", &Names::new(16).unwrap());
+        assert!(source.contains("pub struct SixteenthPuzzle {"));
+        assert!(source.contains(
+            "register!(\n    16,\n    \"Sixteenth Puzzle - Proboscidea \\\"Volcanium\\\"\",\n    \"16.txt\",\n    SixteenthPuzzle,\n    EXAMPLES,\n    generate\n);"
+        ));
+        assert!(source.contains("PuzzleInfo::new(16, \"Test\", EXAMPLES[0].input)"));
+    }
+
+    #[test]
+    fn module_registered() {
+        let source = "use crate::Registration;
+
+puzzles! {
+    #[path = \"puzzles/1.rs\"]
+    pub mod first_puzzle;
+}
+
+#[cfg(test)]
+mod tests {}
+";
+        assert_eq!(
+            "use crate::Registration;
+
+puzzles! {
+    #[path = \"puzzles/1.rs\"]
+    pub mod first_puzzle;
+    #[path = \"puzzles/2.rs\"]
+    pub mod second_puzzle;
+}
+
+#[cfg(test)]
+mod tests {}
+",
+            register_module(source, 2, "second_puzzle").unwrap()
+        );
+        assert!(register_module("fn main() {}", 2, "second_puzzle").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use code_advent_2022::Solution;
+
+use crate::{cli::VerifyOptions, isolation, report};
+
+// the accepted answers keyed by the puzzle (the day, or the input file in batches) and the
+// number of the part, the rows of multi-line answers are joined by new lines
+pub type Answers<K = u8> = BTreeMap<(K, u8), String>;
+
+enum Verdict {
+    Pass,
+    Fail(String),
+    Missing,
+}
+
+pub fn run(puzzles: &[Box<dyn Solution>], options: &VerifyOptions) -> Result<(), String> {
+    // the accepted answers belong to the inputs, so they are kept next to them by default
+    let answers_path = match &options.answers_path {
+        Some(path) => PathBuf::from(path),
+        None => options.selection.input_source()?.dir().join("answers.txt"),
+    };
+    let answers = load_answers(&answers_path)?;
+
+    let mut counts = [0; 3];
+    let mut errors = 0;
+    let mut recorded = Answers::new();
+    for puzzle in puzzles {
+        let day = puzzle.puzzle_info().day();
+        for part in options.selection.parts() {
+            let name = puzzle.puzzle_info().name();
+            let answer = match isolation::solve(puzzle.as_ref(), part) {
+                Ok(answer) => answer.to_string(),
+                Err(error) => {
+                    report::print_error(name, part, &error);
+                    errors += 1;
+                    continue;
+                }
+            };
+
+            let verdict = match answers.get(&(day, part.number())) {
+                Some(expected) if *expected == answer => Verdict::Pass,
+                Some(expected) => Verdict::Fail(expected.clone()),
+                None => Verdict::Missing,
+            };
+            match verdict {
+                Verdict::Pass => {
+                    println!("{name}: The {part} part passed.");
+                    counts[0] += 1;
+                }
+                Verdict::Fail(expected) => {
+                    println!(
+                        "{name}: The {part} part failed, expected:\n{expected}\nfound:\n{answer}"
+                    );
+                    counts[1] += 1;
+                }
+                Verdict::Missing => {
+                    println!("{name}: The {part} part has no accepted answer, found:\n{answer}");
+                    recorded.insert((day, part.number()), answer);
+                    counts[2] += 1;
+                }
+            }
+        }
+    }
+
+    println!(
+        "{} passed, {} failed, {} missing",
+        counts[0], counts[1], counts[2]
+    );
+    if options.record && !recorded.is_empty() {
+        record_answers(&answers_path, &recorded)?;
+        println!(
+            "{} answer(s) recorded into {}",
+            recorded.len(),
+            answers_path.display()
+        );
+    }
+
+    if counts[1] > 0 {
+        return Err(format!(
+            "{} part(s) do not match the accepted answers",
+            counts[1]
+        ));
+    }
+    match errors {
+        0 => Ok(()),
+        _ => Err(format!("{errors} part(s) could not be solved")),
+    }
+}
+
+// every line holds the day (or the file name), the part and the answer separated by spaces,
+// multi-line answers repeat the day and the part for each of their rows
+pub fn load_answers<K: FromStr + Ord>(path: &Path) -> Result<Answers<K>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Ok(Answers::new()),
+    };
+
+    let mut answers = Answers::new();
+    let lines = content.lines().enumerate();
+    for (idx, line) in lines.filter(|(_, line)| !line.is_empty() && !line.starts_with('#')) {
+        let mut fields = line.splitn(3, ' ');
+        let (puzzle, part, answer) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(puzzle), Some(part), Some(answer)) => (puzzle.parse(), part.parse(), answer),
+            _ => {
+                return Err(format!(
+                    "Invalid answer in {} on line {}",
+                    path.display(),
+                    idx + 1
+                ))
+            }
+        };
+        let key = match (puzzle, part) {
+            (Ok(puzzle), Ok(part @ 1..=2)) => (puzzle, part),
+            _ => {
+                return Err(format!(
+                    "Invalid answer in {} on line {}",
+                    path.display(),
+                    idx + 1
+                ))
+            }
+        };
+        answers
+            .entry(key)
+            .and_modify(|rows: &mut String| {
+                rows.push('\n');
+                rows.push_str(answer);
+            })
+            .or_insert_with(|| String::from(answer));
+    }
+    Ok(answers)
+}
+
+fn record_answers(path: &Path, answers: &Answers) -> Result<(), String> {
+    let content: String = answers
+        .iter()
+        .flat_map(|((day, part), answer)| {
+            answer
+                .lines()
+                .map(move |row| format!("{day} {part} {row}\n"))
+        })
+        .collect();
+
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|error| format!("Not able to record the answers: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_answers_round_trip() {
+        let path = std::env::temp_dir().join("code_advent_2022_answers.txt");
+        let _ = fs::remove_file(&path);
+        let mut answers = Answers::new();
+        answers.insert((5, 1), String::from("CMZ"));
+        answers.insert((10, 2), String::from("##..\n..##"));
+
+        record_answers(&path, &answers).unwrap();
+        assert_eq!(answers, load_answers::<u8>(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn invalid_answers() {
+        let path = std::env::temp_dir().join("code_advent_2022_invalid_answers.txt");
+        fs::write(&path, "# day part answer\n1 3 24000\n").unwrap();
+        let error = load_answers::<u8>(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(error.ends_with("on line 2"));
+    }
+}
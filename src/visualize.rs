@@ -0,0 +1,217 @@
+use std::fmt;
+
+use crate::{Framebuffer, Part, Point2};
+
+// the simulations drawn frame by frame, the frames are handed over one by one as there may be
+// thousands of them, the sink copies the ones it keeps
+pub trait Visualize {
+    type Model;
+
+    fn frames(&self, model: &Self::Model, part: Part, sink: &mut dyn FnMut(&Frame));
+}
+
+// a cell of the frame, shown as the glyph in the text and as the color in the images
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Pixel {
+    pub glyph: char,
+    pub color: [u8; 3],
+}
+
+impl Pixel {
+    pub const fn new(glyph: char, color: [u8; 3]) -> Self {
+        Self { glyph, color }
+    }
+}
+
+pub const EMPTY: Pixel = Pixel::new('.', [24, 24, 24]);
+pub const LIT: Pixel = Pixel::new('#', [230, 230, 230]);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    pixels: Vec<Pixel>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![EMPTY; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // the pixels outside of the frame are left out
+    pub fn set(&mut self, Point2 { x, y }: Point2<i64>, pixel: Pixel) {
+        if (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y) {
+            self.pixels[y as usize * self.width + x as usize] = pixel;
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Pixel {
+        self.pixels[y * self.width + x]
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[Pixel]> {
+        self.pixels.chunks(self.width.max(1)).take(self.height)
+    }
+
+    // the glyphs colored by the 24-bit escape codes
+    pub fn to_ansi(&self) -> String {
+        let mut ansi = String::new();
+        for row in self.rows() {
+            for pixel in row {
+                let [r, g, b] = pixel.color;
+                ansi.push_str(&format!("\x1b[38;2;{r};{g};{b}m{}", pixel.glyph));
+            }
+            ansi.push_str("\x1b[0m\n");
+        }
+        ansi
+    }
+
+    // binary portable pixmap
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.extend(self.pixels.iter().flat_map(|pixel| pixel.color));
+        ppm
+    }
+
+    // binary portable graymap, the colors are turned into their luminance
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut pgm = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        pgm.extend(self.pixels.iter().map(|pixel| {
+            let [r, g, b] = pixel.color.map(u32::from);
+            ((299 * r + 587 * g + 114 * b) / 1000) as u8
+        }));
+        pgm
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rows: Vec<String> = self
+            .rows()
+            .map(|row| row.iter().map(|pixel| pixel.glyph).collect())
+            .collect();
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+impl From<&Framebuffer> for Frame {
+    fn from(framebuffer: &Framebuffer) -> Self {
+        let mut frame = Frame::new(framebuffer.width(), framebuffer.height());
+        for y in 0..framebuffer.height() {
+            for x in 0..framebuffer.width() {
+                if framebuffer.is_lit(x, y) {
+                    frame.set(Point2::new(x as i64, y as i64), LIT);
+                }
+            }
+        }
+        frame
+    }
+}
+
+// maps the area of the simulation onto a frame of at most the given size, the larger areas are
+// scaled down keeping their aspect ratio
+pub struct Viewport {
+    top_left: Point2<i64>,
+    scale: i64,
+    width: usize,
+    height: usize,
+}
+
+impl Viewport {
+    // the corners are inclusive
+    pub fn new(top_left: Point2<i64>, bottom_right: Point2<i64>, max_size: usize) -> Self {
+        let Point2 {
+            x: width,
+            y: height,
+        } = bottom_right - top_left + Point2::new(1, 1);
+        let max_size = max_size.max(1) as i64;
+        let scale = ((width.max(height) + max_size - 1) / max_size).max(1);
+        Self {
+            top_left,
+            scale,
+            width: ((width + scale - 1) / scale).max(1) as usize,
+            height: ((height + scale - 1) / scale).max(1) as usize,
+        }
+    }
+
+    pub fn frame(&self) -> Frame {
+        Frame::new(self.width, self.height)
+    }
+
+    pub fn scale(&self) -> i64 {
+        self.scale
+    }
+
+    // the position in the frame of the point of the simulation
+    pub fn project(&self, point: Point2<i64>) -> Point2<i64> {
+        let Point2 { x, y } = point - self.top_left;
+        Point2::new(x.div_euclid(self.scale), y.div_euclid(self.scale))
+    }
+
+    // the point of the simulation in the middle of the pixel
+    pub fn unproject(&self, x: usize, y: usize) -> Point2<i64> {
+        let center = self.scale / 2;
+        self.top_left + Point2::new(x as i64, y as i64) * self.scale + Point2::new(center, center)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_rendered() {
+        let mut frame = Frame::new(3, 2);
+        frame.set(Point2::new(1, 0), LIT);
+        frame.set(Point2::new(2, 1), Pixel::new('o', [255, 0, 0]));
+        frame.set(Point2::new(3, 1), LIT);
+        frame.set(Point2::new(-1, 0), LIT);
+        assert_eq!(".#.\n..o", frame.to_string());
+
+        let ppm = frame.to_ppm();
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n"));
+        assert_eq!(11 + 3 * 2 * 3, ppm.len());
+        assert_eq!([255, 0, 0], ppm[ppm.len() - 3..]);
+        let pgm = frame.to_pgm();
+        assert!(pgm.starts_with(b"P5\n3 2\n255\n"));
+        assert_eq!([24, 230, 24, 24, 24, 76], pgm[11..]);
+
+        let ansi = frame.to_ansi();
+        assert!(ansi.starts_with("\x1b[38;2;24;24;24m."));
+        assert_eq!(2, ansi.matches("\x1b[0m\n").count());
+    }
+
+    #[test]
+    fn viewport_scaled() {
+        let viewport = Viewport::new(Point2::new(-10, 0), Point2::new(29, 9), 20);
+        assert_eq!(2, viewport.scale());
+        assert_eq!(
+            (20, 5),
+            (viewport.frame().width(), viewport.frame().height())
+        );
+        assert_eq!(Point2::new(0, 0), viewport.project(Point2::new(-10, 1)));
+        assert_eq!(Point2::new(19, 4), viewport.project(Point2::new(29, 9)));
+        assert_eq!(Point2::new(-9, 1), viewport.unproject(0, 0));
+
+        let viewport = Viewport::new(Point2::new(0, 0), Point2::new(4, 2), 100);
+        assert_eq!(
+            (1, (5, 3)),
+            (
+                viewport.scale(),
+                (viewport.frame().width(), viewport.frame().height())
+            )
+        );
+    }
+}